@@ -0,0 +1,427 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Roman-numeral harmonic analysis
+//!
+//! Every chord gets a numeral relative to the key (`I`, `ii`, `V7`, `bVII`)
+//! and a function: diatonic, secondary dominant (`V7/V`), borrowed from the
+//! parallel key (modal mixture) or chromatic. Degrees are counted on the
+//! scale of the key, so in A minor `[C]` is `III`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Chord, ChordQuality, Key, Mode, NoteName, ParsedSong, ScaleDegree, Seventh, Suspension, Triad
+};
+
+/// Harmonic function of a chord in the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChordFunction {
+    /// Built from the scale (harmonic minor V and vii° included)
+    Diatonic,
+    /// Dominant of a scale degree other than the tonic (`V7/V`)
+    SecondaryDominant,
+    /// Taken from the parallel major or minor key (`bVII`, `iv` in major)
+    Borrowed,
+    /// Anything else
+    Chromatic
+}
+
+/// Scale degree a secondary dominant resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct NumeralTarget {
+    pub degree: ScaleDegree,
+    pub triad:  Triad
+}
+
+/// Roman numeral of a chord
+///
+/// Secondary dominants are written relative to their target (`V7/V`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct RomanNumeral {
+    /// Degree on the scale of the key, shift relative to that scale
+    pub degree:   ScaleDegree,
+    pub triad:    Triad,
+    /// Quality marks after the numeral (`7`, `ø7`, `maj7`, `sus4`)
+    pub suffix:   String,
+    pub function: ChordFunction,
+    pub target:   Option<NumeralTarget>
+}
+
+/// Analysis of a single chord in a section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordAnalysis {
+    /// Line index in the section
+    pub line:     usize,
+    /// Character position in the line
+    pub position: usize,
+    pub chord:    Chord,
+    /// `None` when the chord root or quality is not recognized
+    pub numeral:  Option<RomanNumeral>,
+    /// Numeral as text (`V7/V`), for display
+    pub label:    Option<String>
+}
+
+/// Analysis of one song section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct SectionAnalysis {
+    /// Index in `ParsedSong::sections`
+    pub section: usize,
+    pub chords:  Vec<ChordAnalysis>
+}
+
+/// Harmonic analysis of a song
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct SongAnalysis {
+    pub key:      Key,
+    pub sections: Vec<SectionAnalysis>
+}
+
+/// Analyze a song in its declared or detected key
+///
+/// `None` when the song has neither `{key}` nor chords.
+pub fn analyze_song(song: &ParsedSong) -> Option<SongAnalysis> {
+    Some(analyze_song_in_key(song, &song.estimated_key()?))
+}
+
+/// Analyze a song in the given key
+///
+/// Recalled sections and chord grids have no positioned chords and come out
+/// empty.
+pub fn analyze_song_in_key(song: &ParsedSong, key: &Key) -> SongAnalysis {
+    let sections = song
+        .sections
+        .iter()
+        .enumerate()
+        .map(|(index, section)| SectionAnalysis {
+            section: index,
+            chords:  section
+                .lines
+                .iter()
+                .enumerate()
+                .flat_map(|(line, song_line)| {
+                    song_line.chords.iter().map(move |positioned| {
+                        let numeral = RomanNumeral::analyze(&positioned.chord, key);
+                        ChordAnalysis {
+                            line,
+                            position: positioned.position,
+                            chord: positioned.chord.clone(),
+                            label: numeral.as_ref().map(ToString::to_string),
+                            numeral
+                        }
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    SongAnalysis {
+        key: *key,
+        sections
+    }
+}
+
+impl RomanNumeral {
+    /// Numeral and function of a chord in a key
+    ///
+    /// The root is respelled for the key first (see [`Key::respell`]).
+    pub fn analyze(chord: &Chord, key: &Key) -> Option<Self> {
+        let root = key.respell(chord.root_note()?);
+        let quality = chord.structure.as_ref()?;
+        let degree = degree_in(root, key);
+
+        let mut target = None;
+        let function = if fits(root, quality, key, true) {
+            ChordFunction::Diatonic
+        } else if fits(root, quality, &parallel(key), false) {
+            ChordFunction::Borrowed
+        } else if let Some(resolution) = secondary_target(root, quality, key) {
+            target = Some(resolution);
+            ChordFunction::SecondaryDominant
+        } else {
+            ChordFunction::Chromatic
+        };
+
+        Some(Self {
+            degree,
+            triad: quality.sounding_triad(),
+            suffix: suffix(quality),
+            function,
+            target
+        })
+    }
+}
+
+impl fmt::Display for RomanNumeral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Some(target) => {
+                write!(f, "V{}/", self.suffix)?;
+                write_numeral(f, target.degree, target.triad)
+            }
+            None => {
+                write_numeral(f, self.degree, self.triad)?;
+                f.write_str(&self.suffix)
+            }
+        }
+    }
+}
+
+fn write_numeral(f: &mut fmt::Formatter<'_>, degree: ScaleDegree, triad: Triad) -> fmt::Result {
+    const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+    let accidental = if degree.shift > 0 { "#" } else { "b" };
+    for _ in 0..degree.shift.unsigned_abs() {
+        f.write_str(accidental)?;
+    }
+
+    let numeral = NUMERALS[(degree.degree - 1) as usize];
+    match triad {
+        Triad::Major | Triad::Augmented => f.write_str(numeral),
+        Triad::Minor | Triad::Diminished => f.write_str(&numeral.to_lowercase())
+    }
+}
+
+/// Quality marks written after the numeral
+fn suffix(quality: &ChordQuality) -> String {
+    let top = quality.extension.unwrap_or(7).to_string();
+    let mut suffix = match (quality.sounding_triad(), quality.seventh) {
+        (Triad::Diminished, Some(Seventh::Minor)) => format!("ø{}", top),
+        (Triad::Diminished, Some(Seventh::Diminished)) => format!("°{}", top),
+        (Triad::Diminished, _) => "°".to_string(),
+        (Triad::Augmented, Some(Seventh::Major)) => format!("+maj{}", top),
+        (Triad::Augmented, Some(_)) => format!("+{}", top),
+        (Triad::Augmented, None) => "+".to_string(),
+        (_, Some(Seventh::Major)) => format!("maj{}", top),
+        (_, Some(_)) => top,
+        (_, None) => String::new()
+    };
+
+    match quality.suspension {
+        Some(Suspension::Sus2) => suffix.push_str("sus2"),
+        Some(Suspension::Sus4) => suffix.push_str("sus4"),
+        None => {}
+    }
+    suffix
+}
+
+/// Degree of a note on the scale of the key, by letter
+fn degree_in(note: NoteName, key: &Key) -> ScaleDegree {
+    let index = (note.letter.index() + 7 - key.tonic.letter.index()) % 7;
+    let scale_note = key.scale()[index as usize];
+
+    ScaleDegree {
+        degree: index + 1,
+        shift:  note.shift - scale_note.shift
+    }
+}
+
+/// Parallel major or minor key
+fn parallel(key: &Key) -> Key {
+    let mode = match key.mode {
+        Mode::Major => Mode::Minor,
+        Mode::Minor => Mode::Major
+    };
+    Key::new(key.tonic, mode)
+}
+
+/// Triad and seventh built from the scale on a degree
+fn diatonic_chord(key: &Key, index: usize) -> (Triad, Seventh) {
+    let scale = key.scale();
+    let root = scale[index].note().to_semitone();
+    let above = |steps: usize| (scale[(index + steps) % 7].note().to_semitone() + 12 - root) % 12;
+
+    let triad = match (above(2), above(4)) {
+        (4, 8) => Triad::Augmented,
+        (4, _) => Triad::Major,
+        (_, 6) => Triad::Diminished,
+        _ => Triad::Minor
+    };
+    let seventh = match above(6) {
+        11 => Seventh::Major,
+        10 => Seventh::Minor,
+        _ => Seventh::Diminished
+    };
+    (triad, seventh)
+}
+
+/// Whether the chord is built from the scale of the key
+///
+/// Sevenths must match the scale too. With `harmonic`, minor keys also
+/// accept the major V (V7) and the diminished leading-tone chord.
+fn fits(root: NoteName, quality: &ChordQuality, key: &Key, harmonic: bool) -> bool {
+    let degree = degree_in(root, key);
+    let index = (degree.degree - 1) as usize;
+
+    if harmonic && key.mode == Mode::Minor {
+        let dominant = degree.degree == 5
+            && degree.shift == 0
+            && quality.sounding_triad() == Triad::Major
+            && quality.seventh.is_none_or(|s| s == Seventh::Minor);
+        let leading = degree.degree == 7
+            && degree.shift == 1
+            && quality.sounding_triad() == Triad::Diminished
+            && quality.seventh != Some(Seventh::Major);
+        if dominant || leading {
+            return true;
+        }
+    }
+
+    if degree.shift != 0 {
+        return false;
+    }
+
+    let (triad, seventh) = diatonic_chord(key, index);
+    let triad_fits = quality.sounding_triad() == triad
+        || quality.suspension.is_some()
+        || quality.is_power_chord();
+    triad_fits && quality.seventh.is_none_or(|s| s == seventh)
+}
+
+/// Scale degree that a dominant-quality chord resolves to, a fifth below
+fn secondary_target(root: NoteName, quality: &ChordQuality, key: &Key) -> Option<NumeralTarget> {
+    if quality.sounding_triad() != Triad::Major
+        || quality.seventh.is_some_and(|s| s != Seventh::Minor)
+    {
+        return None;
+    }
+
+    let target = NoteName::spelled_as(root.note().transpose(5), root.letter.step(3));
+    let index = key.scale().iter().position(|n| *n == target)?;
+    let (triad, _) = diatonic_chord(key, index);
+
+    (index != 0 && matches!(triad, Triad::Major | Triad::Minor)).then_some(NumeralTarget {
+        degree: ScaleDegree {
+            degree: index as u8 + 1,
+            shift:  0
+        },
+        triad
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChordProParser;
+
+    fn numeral(chord: &str, key: &str) -> RomanNumeral {
+        RomanNumeral::analyze(&Chord::parse(chord).unwrap(), &Key::parse(key).unwrap()).unwrap()
+    }
+
+    fn label(chord: &str, key: &str) -> String {
+        numeral(chord, key).to_string()
+    }
+
+    #[test]
+    fn test_diatonic_major() {
+        assert_eq!(label("C", "C"), "I");
+        assert_eq!(label("Dm", "C"), "ii");
+        assert_eq!(label("Em7", "C"), "iii7");
+        assert_eq!(label("Fmaj7", "C"), "IVmaj7");
+        assert_eq!(label("G7", "C"), "V7");
+        assert_eq!(label("Bm7b5", "C"), "viiø7");
+        assert_eq!(label("Bdim", "C"), "vii°");
+        assert_eq!(label("Gsus4", "C"), "Vsus4");
+        assert_eq!(numeral("Am", "C").function, ChordFunction::Diatonic);
+    }
+
+    #[test]
+    fn test_diatonic_minor() {
+        assert_eq!(label("Am", "Am"), "i");
+        assert_eq!(label("C", "Am"), "III");
+        assert_eq!(label("G", "Am"), "VII");
+        assert_eq!(label("E7", "Am"), "V7");
+        assert_eq!(label("G#dim7", "Am"), "#vii°7");
+        assert_eq!(numeral("E7", "Am").function, ChordFunction::Diatonic);
+        assert_eq!(numeral("G#dim7", "Am").function, ChordFunction::Diatonic);
+    }
+
+    #[test]
+    fn test_secondary_dominants() {
+        let d7 = numeral("D7", "C");
+        assert_eq!(d7.function, ChordFunction::SecondaryDominant);
+        assert_eq!(d7.to_string(), "V7/V");
+        assert_eq!(d7.degree.degree, 2);
+
+        assert_eq!(label("E", "C"), "V/vi");
+        assert_eq!(label("C7", "C"), "V7/IV");
+        assert_eq!(label("A7", "C"), "V7/ii");
+        assert_eq!(label("F#7", "G"), "V7/iii");
+    }
+
+    #[test]
+    fn test_borrowed_chords() {
+        let bb = numeral("Bb", "C");
+        assert_eq!(bb.function, ChordFunction::Borrowed);
+        assert_eq!(bb.to_string(), "bVII");
+
+        assert_eq!(label("Ab", "C"), "bVI");
+        assert_eq!(label("Fm", "C"), "iv");
+        assert_eq!(numeral("Fm", "C").function, ChordFunction::Borrowed);
+        assert_eq!(numeral("D", "Am").function, ChordFunction::Borrowed);
+        assert_eq!(label("A", "Am"), "I");
+        assert_eq!(label("A7", "Am"), "V7/iv");
+    }
+
+    #[test]
+    fn test_sharp_spelling_in_flat_key() {
+        let chord = numeral("A#", "Bb");
+        assert_eq!(chord.function, ChordFunction::Diatonic);
+        assert_eq!(chord.to_string(), "I");
+        assert_eq!(label("D#", "Bb"), "IV");
+        assert_eq!(label("G#", "Bb"), "bVII");
+        assert_eq!(label("C#dim7", "Dm"), "#vii°7");
+    }
+
+    #[test]
+    fn test_chromatic() {
+        let chord = numeral("Db7", "C");
+        assert_eq!(chord.function, ChordFunction::Chromatic);
+        assert_eq!(chord.to_string(), "bII7");
+    }
+
+    #[test]
+    fn test_unrecognized_chord() {
+        let chord = Chord::parse("Cxyz").unwrap();
+        assert!(RomanNumeral::analyze(&chord, &Key::parse("C").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_analyze_song() {
+        let content = "{key: G}\n{sov}\n[G]Amazing [D/F#]grace\n[Em]How [A7]sweet [D]the sound\n{eov}\n{soc}\n[C]Saved [Cm]a [G]wretch\n{eoc}";
+        let song = ChordProParser::parse(content);
+        let analysis = analyze_song(&song).unwrap();
+
+        assert_eq!(analysis.key.to_string(), "G");
+        assert_eq!(analysis.sections.len(), 2);
+
+        let verse = &analysis.sections[0];
+        let labels: Vec<&str> = verse
+            .chords
+            .iter()
+            .map(|c| c.label.as_deref().unwrap())
+            .collect();
+        assert_eq!(labels, ["I", "V", "vi", "V7/V", "V"]);
+        assert_eq!(verse.chords[2].line, 1);
+        assert_eq!(verse.chords[3].position, 4);
+
+        let chorus = &analysis.sections[1];
+        let cm = chorus.chords[1].numeral.as_ref().unwrap();
+        assert_eq!(cm.function, ChordFunction::Borrowed);
+        assert_eq!(chorus.section, 1);
+    }
+
+    #[test]
+    fn test_analyze_song_without_key_or_chords() {
+        assert!(analyze_song(&ChordProParser::parse("Just text")).is_none());
+    }
+}
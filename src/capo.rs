@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Capo calculator
+//!
+//! Written chords are shapes played with `{capo}` (none = open), so the
+//! sounding chords are the written ones raised by the capo. `{key}` is the
+//! sounding key. For each capo position the sounding chords are lowered back
+//! to shapes and ranked by how many of them are easy open shapes.
+
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Chord, ChordQuality, Key, Note, ParsedSong, detect_key, transpose::transpose_chord_in
+};
+
+/// Highest capo position suggested
+pub const MAX_CAPO: u8 = 7;
+
+/// Easy open-position guitar chords
+const OPEN_SHAPES: &[&str] = &[
+    "C", "A", "G", "E", "D", "Am", "Em", "Dm", "C7", "A7", "G7", "E7", "D7", "B7", "Am7", "Em7",
+    "Dm7", "Cmaj7", "Fmaj7", "Amaj7", "Dmaj7", "Asus2", "Asus4", "Dsus2", "Dsus4", "Esus4",
+    "Cadd9", "E5", "A5", "D5"
+];
+
+/// Parsed open shapes (root and quality)
+static OPEN_SHAPE_SET: LazyLock<Vec<(Note, ChordQuality)>> = LazyLock::new(|| {
+    OPEN_SHAPES
+        .iter()
+        .filter_map(|s| Chord::parse(s))
+        .filter_map(|c| Some((Note::parse(&c.root)?.0, c.structure?)))
+        .collect()
+});
+
+/// Capo position with the chord shapes to play
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct CapoSuggestion {
+    pub capo:        u8,
+    /// Key of the shapes, when the song key is known
+    pub shape_key:   Option<Key>,
+    /// Distinct shape chords in order of appearance
+    pub chords:      Vec<Chord>,
+    /// How many of `chords` are easy open shapes
+    pub open_chords: usize
+}
+
+/// Whether the chord is an easy open-position shape (bass note ignored)
+pub fn is_open_shape(chord: &Chord) -> bool {
+    let (Some((root, _)), Some(quality)) = (Note::parse(&chord.root), &chord.structure) else {
+        return false;
+    };
+    OPEN_SHAPE_SET
+        .iter()
+        .any(|(note, shape)| *note == root && shape == quality)
+}
+
+/// Sounding key of the song: `{key}`, or the key detected from the written
+/// shapes raised by the capo
+pub fn sounding_key(song: &ParsedSong) -> Option<Key> {
+    match song.key.as_deref().and_then(Key::parse) {
+        Some(key) => Some(key),
+        None => Some(
+            detect_key(song)
+                .first()?
+                .key
+                .transpose(song.capo.unwrap_or(0))
+        )
+    }
+}
+
+/// Capo positions from 0 to [`MAX_CAPO`], most open shapes first
+///
+/// Positions with the same number of open shapes are ordered by capo.
+pub fn suggest_capo(song: &ParsedSong) -> Vec<CapoSuggestion> {
+    let sounding = sounding_key(song);
+    let written_capo = song.capo.unwrap_or(0);
+
+    let mut distinct: Vec<&Chord> = Vec::new();
+    for chord in song.chords() {
+        if !distinct.iter().any(|d| d.same_meaning(chord)) {
+            distinct.push(chord);
+        }
+    }
+
+    let mut suggestions: Vec<CapoSuggestion> = (0..=MAX_CAPO)
+        .map(|capo| {
+            let shift = written_capo - capo as i32;
+            let shape_key = sounding.map(|key| key.transpose(-(capo as i32)));
+            let chords: Vec<Chord> = distinct
+                .iter()
+                .map(|chord| transpose_chord_in(chord, shift, shape_key.as_ref()))
+                .collect();
+
+            CapoSuggestion {
+                capo,
+                shape_key,
+                open_chords: chords.iter().filter(|c| is_open_shape(c)).count(),
+                chords
+            }
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| b.open_chords.cmp(&a.open_chords).then(a.capo.cmp(&b.capo)));
+    suggestions
+}
+
+/// Song as played with the given capo: chords are shapes, `{key}` keeps the
+/// sounding key
+///
+/// Chord definitions that cannot be moved (open strings) are dropped.
+pub fn capo_view(song: &ParsedSong, capo: u8) -> ParsedSong {
+    let sounding = sounding_key(song);
+    let shift = song.capo.unwrap_or(0) - capo as i32;
+    let shape_key = sounding.map(|key| key.transpose(-(capo as i32)));
+
+    let mut view = song.clone();
+    view.map_chords(|chord| transpose_chord_in(chord, shift, shape_key.as_ref()));
+
+    let use_flats = shape_key.is_some_and(|key| key.uses_flats());
+    view.definitions = song
+        .definitions
+        .iter()
+        .filter_map(|d| d.transpose(shift, use_flats))
+        .collect();
+    view.key = sounding.map(|key| key.to_string()).or(view.key);
+    view.capo = (capo > 0).then_some(capo as i32);
+    view
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChordProParser;
+
+    fn names(chords: &[Chord]) -> Vec<String> {
+        chords.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_is_open_shape() {
+        assert!(is_open_shape(&Chord::parse("G").unwrap()));
+        assert!(is_open_shape(&Chord::parse("Am7").unwrap()));
+        assert!(is_open_shape(&Chord::parse("C/E").unwrap()));
+        assert!(is_open_shape(&Chord::parse("Amin7").unwrap()));
+        assert!(!is_open_shape(&Chord::parse("F").unwrap()));
+        assert!(!is_open_shape(&Chord::parse("Bb").unwrap()));
+        assert!(!is_open_shape(&Chord::parse("F#m").unwrap()));
+    }
+
+    #[test]
+    fn test_suggest_capo_for_flat_key() {
+        let song = ChordProParser::parse("{key: Eb}\n[Eb]Hello [Ab]world [Bb]again [Cm]more");
+        let suggestions = suggest_capo(&song);
+
+        assert_eq!(suggestions.len(), MAX_CAPO as usize + 1);
+        let best = &suggestions[0];
+        assert_eq!(best.open_chords, 3);
+        assert_eq!(best.capo, 1);
+        assert_eq!(best.shape_key.unwrap().to_string(), "D");
+        assert_eq!(names(&best.chords), ["D", "G", "A", "Bm"]);
+
+        let capo3 = suggestions.iter().find(|s| s.capo == 3).unwrap();
+        assert_eq!(names(&capo3.chords), ["C", "F", "G", "Am"]);
+        assert_eq!(capo3.open_chords, 3);
+    }
+
+    #[test]
+    fn test_suggest_capo_prefers_lower_position() {
+        let song = ChordProParser::parse("[G]Hello [C]world [D]again");
+        let best = &suggest_capo(&song)[0];
+        assert_eq!(best.capo, 0);
+        assert_eq!(best.open_chords, 3);
+    }
+
+    #[test]
+    fn test_suggest_capo_respects_written_capo() {
+        let song = ChordProParser::parse("{capo: 3}\n[C]Hello [F]world [G]again [C]");
+        let suggestions = suggest_capo(&song);
+        let capo3 = suggestions.iter().find(|s| s.capo == 3).unwrap();
+        assert_eq!(names(&capo3.chords), ["C", "F", "G"]);
+
+        let open = suggestions.iter().find(|s| s.capo == 0).unwrap();
+        assert_eq!(names(&open.chords), ["Eb", "Ab", "Bb"]);
+        assert_eq!(open.shape_key.unwrap().to_string(), "Eb");
+    }
+
+    #[test]
+    fn test_sounding_key() {
+        let song = ChordProParser::parse("{key: Eb}\n{capo: 3}\n[C]Hello [G]world");
+        assert_eq!(sounding_key(&song).unwrap().to_string(), "Eb");
+
+        let song = ChordProParser::parse("{capo: 2}\n[G]Hello [D]world [G]");
+        assert_eq!(sounding_key(&song).unwrap().to_string(), "A");
+    }
+
+    #[test]
+    fn test_capo_view() {
+        let song = ChordProParser::parse(
+            "{key: Bb}\n{define: Bb base-fret 1 frets x 1 3 3 3 1}\n[Bb]Hello [F/A]world [Gm]again"
+        );
+        let view = capo_view(&song, 3);
+
+        assert_eq!(view.capo, Some(3));
+        assert_eq!(view.key.as_deref(), Some("Bb"));
+        assert_eq!(
+            names(&view.chords().into_iter().cloned().collect::<Vec<_>>()),
+            ["G", "D/F#", "Em"]
+        );
+        assert_eq!(view.definitions[0].name, "G");
+
+        let back = capo_view(&view, 0);
+        assert_eq!(back.capo, None);
+        assert_eq!(back.sections, song.sections);
+    }
+}
@@ -66,6 +66,55 @@ impl SongCategory {
         }
     }
 
+    /// Get English display name
+    pub fn name_en(&self) -> &'static str {
+        match self {
+            Self::Praise => "Praise",
+            Self::Worship => "Worship",
+            Self::Christmas => "Christmas",
+            Self::Easter => "Easter",
+            Self::Wedding => "Wedding",
+            Self::Funeral => "Funeral",
+            Self::Youth => "Youth",
+            Self::Children => "Children",
+            Self::Communion => "Communion",
+            Self::Baptism => "Baptism",
+            Self::Prayer => "Prayer",
+            Self::Thanksgiving => "Thanksgiving",
+            Self::Evangelism => "Evangelism",
+            Self::Repentance => "Repentance",
+            Self::Faith => "Faith",
+            Self::Hope => "Hope",
+            Self::Love => "Love",
+            Self::SecondComing => "Second Coming",
+            Self::Heaven => "Heaven",
+            Self::Trinity => "Trinity",
+            Self::HolySpirit => "Holy Spirit",
+            Self::Salvation => "Salvation"
+        }
+    }
+
+    /// Find category by English or Russian name, ignoring case
+    ///
+    /// Used for themes of imported songs; `holy_spirit` and a few common
+    /// synonyms like `Adoration` are accepted too.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace(['_', '-'], " ");
+        let synonym = match name.as_str() {
+            "adoration" => Some(Self::Worship),
+            "lord's supper" | "holy communion" => Some(Self::Communion),
+            "resurrection" => Some(Self::Easter),
+            _ => None
+        };
+
+        synonym.or_else(|| {
+            Self::all().iter().copied().find(|category| {
+                category.name_en().to_lowercase() == name
+                    || category.name_ru().to_lowercase() == name
+            })
+        })
+    }
+
     /// Get all categories
     pub fn all() -> &'static [SongCategory] {
         &[
@@ -132,4 +181,29 @@ mod tests {
         assert_eq!(SongCategory::HolySpirit.name_ru(), "Святой Дух");
         assert_eq!(SongCategory::Salvation.name_ru(), "Спасение");
     }
+
+    #[test]
+    fn test_from_name() {
+        for category in SongCategory::all() {
+            assert_eq!(SongCategory::from_name(category.name_en()), Some(*category));
+            assert_eq!(SongCategory::from_name(category.name_ru()), Some(*category));
+        }
+        assert_eq!(
+            SongCategory::from_name("holy_spirit"),
+            Some(SongCategory::HolySpirit)
+        );
+        assert_eq!(
+            SongCategory::from_name(" christmas "),
+            Some(SongCategory::Christmas)
+        );
+        assert_eq!(
+            SongCategory::from_name("святой дух"),
+            Some(SongCategory::HolySpirit)
+        );
+        assert_eq!(
+            SongCategory::from_name("Adoration"),
+            Some(SongCategory::Worship)
+        );
+        assert_eq!(SongCategory::from_name("Jazz"), None);
+    }
 }
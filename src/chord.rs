@@ -3,15 +3,24 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::Note;
+use super::{
+    ChordQuality, ChordTone, Interval, Key, NashvilleChord, Note, NoteName, NoteNaming,
+    naming::latin_lookalike
+};
 
 /// Parsed chord
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Equality compares the chord as written; [`Chord::meaning`] compares what
+/// it sounds like.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct Chord {
-    pub root:    String,
-    pub quality: String,
-    pub bass:    Option<String>
+    pub root:      String,
+    /// Quality as written, kept for display
+    pub quality:   String,
+    pub bass:      Option<String>,
+    /// Parsed quality, `None` when the spelling is not recognized
+    pub structure: Option<ChordQuality>
 }
 
 impl Chord {
@@ -25,7 +34,10 @@ impl Chord {
         let (main, bass) = if let Some(idx) = s.rfind('/') {
             let bass_part = &s[idx + 1..];
             if Note::parse(bass_part).is_some() {
-                (&s[..idx], Some(bass_part.to_string()))
+                (
+                    &s[..idx],
+                    Some(bass_part.chars().map(latin_lookalike).collect())
+                )
             } else {
                 (s, None)
             }
@@ -38,23 +50,66 @@ impl Chord {
             return None;
         }
 
-        let (root_end, root) = if chars.len() >= 2 && (chars[1] == '#' || chars[1] == 'b') {
-            (2, format!("{}{}", chars[0], chars[1]))
+        let first = latin_lookalike(chars[0]);
+        let (root_chars, root) = if chars.len() >= 2 && (chars[1] == '#' || chars[1] == 'b') {
+            (2, format!("{}{}", first, chars[1]))
         } else {
-            (1, chars[0].to_string())
+            (1, first.to_string())
         };
 
         Note::parse(&root)?;
 
+        let root_end: usize = chars[..root_chars].iter().map(|c| c.len_utf8()).sum();
         let quality = main[root_end..].to_string();
+        let structure = ChordQuality::parse(&quality);
 
         Some(Self {
             root,
             quality,
-            bass
+            bass,
+            structure
         })
     }
 
+    /// Parse chord written in the given naming system (`Hm7`, `Sib/Re`)
+    ///
+    /// The root and bass are stored in English.
+    pub fn parse_with(s: &str, naming: NoteNaming) -> Option<Self> {
+        if naming == NoteNaming::English {
+            return Self::parse(s);
+        }
+
+        let s = s.trim();
+        let (main, bass) = match s
+            .rsplit_once('/')
+            .and_then(|(main, bass)| Some((main, naming.parse_note(bass)?)))
+        {
+            Some((main, bass)) => (main, Some(bass.to_string())),
+            None => (s, None)
+        };
+        let (root, quality) = naming.parse_prefix(main)?;
+
+        Some(Self {
+            root: root.to_string(),
+            quality: quality.to_string(),
+            bass,
+            structure: ChordQuality::parse(quality)
+        })
+    }
+
+    /// Chord written in the given naming system
+    pub fn to_string_with(&self, naming: NoteNaming) -> String {
+        let name = |note: &str| match NoteName::parse(note) {
+            Some(name) => naming.format(name),
+            None => note.to_string()
+        };
+
+        match &self.bass {
+            Some(bass) => format!("{}{}/{}", name(&self.root), self.quality, name(bass)),
+            None => format!("{}{}", name(&self.root), self.quality)
+        }
+    }
+
     /// Transpose chord by semitones
     pub fn transpose(&self, semitones: i32, use_flats: bool) -> Self {
         let transpose_note = |note_str: &str| -> String {
@@ -71,11 +126,152 @@ impl Chord {
         };
 
         Self {
-            root:    transpose_note(&self.root),
-            quality: self.quality.clone(),
-            bass:    self.bass.as_ref().map(|b| transpose_note(b))
+            root:      transpose_note(&self.root),
+            quality:   self.quality.clone(),
+            bass:      self.bass.as_ref().map(|b| transpose_note(b)),
+            structure: self.structure.clone()
+        }
+    }
+}
+
+impl Chord {
+    /// Transpose chord by semitones, spelling root and bass in the target key
+    ///
+    /// Scale notes take the scale spelling. Other notes keep their written
+    /// letter, moved by as many steps as the tonic: a borrowed `Ab` in C
+    /// becomes `Bb` in D, not `A#`.
+    pub fn transpose_in_key(&self, semitones: i32, key: &Key) -> Self {
+        let source = key.transpose(-semitones);
+        let steps = (key.tonic.letter.index() + 7 - source.tonic.letter.index()) % 7;
+        let interval = Interval::new(steps + 1, semitones.rem_euclid(12) as u8);
+
+        let transpose_note = |note_str: &str| -> String {
+            let Some((note, _)) = Note::parse(note_str) else {
+                return note_str.to_string();
+            };
+            let note = note.transpose(semitones);
+
+            key.scale()
+                .into_iter()
+                .find(|name| name.note() == note)
+                .or_else(|| {
+                    NoteName::parse(note_str)
+                        .map(|name| name.above(interval))
+                        .filter(|name| name.shift.abs() <= 1)
+                })
+                .unwrap_or_else(|| key.spell(note))
+                .to_string()
+        };
+
+        Self {
+            root:      transpose_note(&self.root),
+            quality:   self.quality.clone(),
+            bass:      self.bass.as_ref().map(|b| transpose_note(b)),
+            structure: self.structure.clone()
+        }
+    }
+
+    /// Nashville number of the chord in a key
+    pub fn to_nashville(&self, key: &Key) -> Option<NashvilleChord> {
+        NashvilleChord::from_chord(self, key)
+    }
+
+    /// Spelled root note
+    pub fn root_note(&self) -> Option<NoteName> {
+        NoteName::parse(&self.root)
+    }
+
+    /// Spelled bass note of a slash chord
+    pub fn bass_note(&self) -> Option<NoteName> {
+        self.bass.as_deref().and_then(NoteName::parse)
+    }
+
+    /// Chord tones above the root with their intervals
+    ///
+    /// `None` when the quality is not recognized.
+    pub fn tones(&self) -> Option<Vec<ChordTone>> {
+        let root = self.root_note()?;
+        let intervals = self.structure.as_ref()?.intervals();
+
+        Some(
+            intervals
+                .into_iter()
+                .map(|interval| ChordTone {
+                    note: root.above(interval),
+                    interval
+                })
+                .collect()
+        )
+    }
+
+    /// Notes from the bass up: bass (if it is not the root), then chord tones
+    pub fn notes(&self) -> Option<Vec<NoteName>> {
+        let mut notes: Vec<NoteName> = self.tones()?.into_iter().map(|t| t.note).collect();
+
+        if let Some(bass) = self.bass_note() {
+            notes.retain(|n| n.note() != bass.note());
+            notes.insert(0, bass);
         }
+
+        Some(notes)
     }
+
+    /// Whether the chord symbol is theoretically valid
+    ///
+    /// The quality must be recognized and free of conflicts, and every tone
+    /// must be spellable with at most a double accidental.
+    pub fn is_valid(&self) -> bool {
+        self.structure.as_ref().is_some_and(ChordQuality::is_valid)
+            && self
+                .tones()
+                .is_some_and(|tones| tones.iter().all(|t| t.note.is_practical()))
+    }
+
+    /// What the chord means, for comparing and de-duplicating chords
+    /// regardless of spelling
+    pub fn meaning(&self) -> ChordMeaning {
+        fn pitch(s: &str) -> Pitch {
+            Note::parse(s)
+                .map(|(note, _)| note)
+                .ok_or_else(|| s.to_string())
+        }
+
+        let intervals = match &self.structure {
+            Some(structure) => {
+                let mut intervals = structure.intervals();
+                intervals.sort();
+                intervals.dedup();
+                Ok(intervals)
+            }
+            None => Err(self.quality.clone())
+        };
+
+        ChordMeaning {
+            root: pitch(&self.root),
+            bass: self.bass.as_deref().map(pitch),
+            intervals
+        }
+    }
+
+    /// Whether two chords mean the same, like `A#maj7` and `BbΔ`
+    pub fn same_meaning(&self, other: &Self) -> bool {
+        self.meaning() == other.meaning()
+    }
+}
+
+/// Parsed note, or the text when it does not parse
+type Pitch = Result<Note, String>;
+
+/// Chord by meaning: pitch classes of root and bass with the sorted
+/// intervals
+///
+/// `A#`, `Bb` and `Hb` are the same root, `maj7`, `M7` and `Δ` the same
+/// quality. Spellings that do not parse are compared as written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChordMeaning {
+    root:      Pitch,
+    bass:      Option<Pitch>,
+    intervals: Result<Vec<Interval>, String>
 }
 
 impl std::fmt::Display for Chord {
@@ -88,7 +284,7 @@ impl std::fmt::Display for Chord {
 }
 
 /// Chord with position in text
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct PositionedChord {
     pub position: usize,
@@ -159,6 +355,85 @@ mod tests {
         assert!(chord.bass.is_none());
     }
 
+    #[test]
+    fn test_parse_structure() {
+        let chord = Chord::parse("F#m7b5/C").unwrap();
+        let structure = chord.structure.unwrap();
+        assert_eq!(structure.triad, crate::Triad::Minor);
+        assert_eq!(structure.seventh, Some(crate::Seventh::Minor));
+        assert_eq!(structure.alterations.len(), 1);
+        assert_eq!(chord.quality, "m7b5");
+
+        let chord = Chord::parse("CΔ9").unwrap();
+        assert_eq!(chord.quality, "Δ9");
+        assert_eq!(chord.structure.unwrap().extension, Some(9));
+    }
+
+    #[test]
+    fn test_parse_unrecognized_structure() {
+        let chord = Chord::parse("Am/X").unwrap();
+        assert!(chord.structure.is_none());
+    }
+
+    #[test]
+    fn test_transpose_keeps_structure() {
+        let chord = Chord::parse("Dmaj7").unwrap();
+        assert_eq!(chord.transpose(2, false).structure, chord.structure);
+    }
+
+    fn spell(notes: Vec<NoteName>) -> Vec<String> {
+        notes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_tones() {
+        let tones = Chord::parse("Ebmaj7").unwrap().tones().unwrap();
+        let spelled: Vec<String> = tones
+            .iter()
+            .map(|t| format!("{}:{}", t.note, t.interval))
+            .collect();
+        assert_eq!(spelled, ["Eb:1", "G:3", "Bb:5", "D:7"]);
+
+        let notes = Chord::parse("F#m7").unwrap().notes().unwrap();
+        assert_eq!(spell(notes), ["F#", "A", "C#", "E"]);
+
+        let notes = Chord::parse("C7#9").unwrap().notes().unwrap();
+        assert_eq!(spell(notes), ["C", "E", "G", "Bb", "D#"]);
+
+        let notes = Chord::parse("Bdim7").unwrap().notes().unwrap();
+        assert_eq!(spell(notes), ["B", "D", "F", "Ab"]);
+    }
+
+    #[test]
+    fn test_notes_with_bass() {
+        let notes = Chord::parse("C/E").unwrap().notes().unwrap();
+        assert_eq!(spell(notes), ["E", "C", "G"]);
+
+        let notes = Chord::parse("Am7/G").unwrap().notes().unwrap();
+        assert_eq!(spell(notes), ["G", "A", "C", "E"]);
+
+        let notes = Chord::parse("D/F#").unwrap().notes().unwrap();
+        assert_eq!(notes[0].note(), Note::FSharp);
+
+        let notes = Chord::parse("G/F").unwrap().notes().unwrap();
+        assert_eq!(spell(notes), ["F", "G", "B", "D"]);
+    }
+
+    #[test]
+    fn test_tones_unrecognized_quality() {
+        assert!(Chord::parse("Am/X").unwrap().tones().is_none());
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(Chord::parse("C").unwrap().is_valid());
+        assert!(Chord::parse("F#m7b5").unwrap().is_valid());
+        assert!(Chord::parse("Gbmaj9").unwrap().is_valid());
+        assert!(!Chord::parse("Cm#9").unwrap().is_valid());
+        assert!(!Chord::parse("Fbdim7").unwrap().is_valid());
+        assert!(!Chord::parse("Cxyz").unwrap().is_valid());
+    }
+
     #[test]
     fn test_transpose_chord() {
         let chord = Chord::parse("C").unwrap();
@@ -181,6 +456,74 @@ mod tests {
         assert_eq!(transposed.bass, Some("A".to_string()));
     }
 
+    #[test]
+    fn test_transpose_in_key() {
+        let f = Key::parse("F").unwrap();
+        assert_eq!(
+            Chord::parse("G7")
+                .unwrap()
+                .transpose_in_key(3, &f)
+                .to_string(),
+            "Bb7"
+        );
+
+        let eb_minor = Key::parse("Ebm").unwrap();
+        let chord = Chord::parse("E/B").unwrap().transpose_in_key(2, &eb_minor);
+        assert_eq!(chord.to_string(), "Gb/Db");
+
+        let d = Key::parse("D").unwrap();
+        let chord = Chord::parse("Bb/F").unwrap().transpose_in_key(0, &d);
+        assert_eq!(chord.to_string(), "Bb/F");
+
+        let borrowed = ["Ab", "Bb", "F#"].map(|root| {
+            Chord::parse(root)
+                .unwrap()
+                .transpose_in_key(2, &d)
+                .to_string()
+        });
+        assert_eq!(borrowed, ["Bb", "C", "G#"]);
+    }
+
+    #[test]
+    fn test_parse_cyrillic_root() {
+        let chord = Chord::parse("Сm7").unwrap();
+        assert_eq!(chord.root, "C");
+        assert_eq!(chord.quality, "m7");
+
+        let chord = Chord::parse("А/Е").unwrap();
+        assert_eq!(chord.to_string(), "A/E");
+
+        assert!(Chord::parse("а").is_none());
+        assert!(Chord::parse("с").is_none());
+        assert!(Chord::parse("е").is_none());
+    }
+
+    #[test]
+    fn test_parse_with_naming() {
+        let chord = Chord::parse_with("Hm7/A", NoteNaming::German).unwrap();
+        assert_eq!(chord.to_string(), "Bm7/A");
+
+        let chord = Chord::parse_with("B/D", NoteNaming::German).unwrap();
+        assert_eq!(chord.to_string(), "Bb/D");
+
+        let chord = Chord::parse_with("Rem7", NoteNaming::Solfege).unwrap();
+        assert_eq!(chord.to_string(), "Dm7");
+        assert!(chord.structure.is_some());
+
+        let chord = Chord::parse_with("Фа#m/До#", NoteNaming::SolfegeCyrillic).unwrap();
+        assert_eq!(chord.to_string(), "F#m/C#");
+
+        assert!(Chord::parse_with("Xm", NoteNaming::German).is_none());
+    }
+
+    #[test]
+    fn test_to_string_with_naming() {
+        let chord = Chord::parse("Bbmaj7/B").unwrap();
+        assert_eq!(chord.to_string_with(NoteNaming::German), "Bmaj7/H");
+        assert_eq!(chord.to_string_with(NoteNaming::Solfege), "Sibmaj7/Si");
+        assert_eq!(chord.to_string_with(NoteNaming::English), "Bbmaj7/B");
+    }
+
     #[test]
     fn test_display_simple_chord() {
         let chord = Chord::parse("Am").unwrap();
@@ -0,0 +1,331 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Custom chord shapes from `{define}` and `{chord}` directives
+//!
+//! Syntax reference: <https://www.chordpro.org/chordpro/directives-define/>
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::Chord;
+
+/// Frets on a typical guitar neck; moved shapes going past it wrap an octave
+/// down
+const NECK_FRETS: u16 = 24;
+
+/// Keywords that start a new part of a definition
+const KEYWORDS: &[&str] = &["base-fret", "frets", "fingers", "keys", "copy", "display"];
+
+/// Chord shape defined inside the song
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordDefinition {
+    pub name:      String,
+    /// Fret shown at the top of the diagram (1 = nut)
+    pub base_fret: u8,
+    /// Fret per string relative to `base_fret`, `None` = muted, `0` = open
+    pub frets:     Vec<Option<u8>>,
+    /// Finger per string, `None` = not fretted
+    pub fingers:   Vec<Option<u8>>,
+    /// Keyboard keys as semitones above the root
+    pub keys:      Vec<u8>,
+    /// Name of a chord whose shape is copied
+    pub copy:      Option<String>,
+    /// Name to display instead of `name`
+    pub display:   Option<String>
+}
+
+impl ChordDefinition {
+    /// Parse directive value like `Asus2 base-fret 1 frets x 0 2 2 0 0`
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut tokens = value.split_whitespace().peekable();
+        let name = tokens.next()?.to_string();
+
+        let mut definition = Self {
+            name,
+            base_fret: 1,
+            frets: Vec::new(),
+            fingers: Vec::new(),
+            keys: Vec::new(),
+            copy: None,
+            display: None
+        };
+
+        while let Some(keyword) = tokens.next() {
+            let mut args = Vec::new();
+            while let Some(arg) =
+                tokens.next_if(|t| !KEYWORDS.contains(&t.to_lowercase().as_str()))
+            {
+                args.push(arg);
+            }
+
+            match keyword.to_lowercase().as_str() {
+                "base-fret" => match args.as_slice() {
+                    [fret] => definition.base_fret = fret.parse().ok().filter(|f| *f > 0)?,
+                    _ => return None
+                },
+                "frets" => {
+                    definition.frets = args
+                        .iter()
+                        .map(|t| Self::parse_fret(t))
+                        .collect::<Option<_>>()?
+                }
+                "fingers" => {
+                    definition.fingers = args
+                        .iter()
+                        .map(|t| Self::parse_finger(t))
+                        .collect::<Option<_>>()?
+                }
+                "keys" => {
+                    definition.keys = args.iter().map(|t| t.parse().ok()).collect::<Option<_>>()?
+                }
+                "copy" => match args.as_slice() {
+                    [name] => definition.copy = Some(name.to_string()),
+                    _ => return None
+                },
+                "display" if !args.is_empty() => definition.display = Some(args.join(" ")),
+                _ => return None
+            }
+        }
+
+        Some(definition)
+    }
+
+    fn parse_fret(token: &str) -> Option<Option<u8>> {
+        match token {
+            "x" | "X" | "N" | "-1" => Some(None),
+            _ => token.parse().ok().map(Some)
+        }
+    }
+
+    fn parse_finger(token: &str) -> Option<Option<u8>> {
+        match token {
+            "-" | "x" | "X" | "N" | "0" => Some(None),
+            _ => token.parse().ok().map(Some)
+        }
+    }
+
+    /// Whether the definition describes a fretted shape
+    pub fn has_frets(&self) -> bool {
+        !self.frets.is_empty()
+    }
+
+    /// Transpose the definition by semitones
+    ///
+    /// Closed shapes move up the neck by changing `base_fret`, an octave
+    /// lower when they would run past the last fret. Shapes with
+    /// open strings cannot be moved, so `None` is returned and the definition
+    /// should be dropped in favour of a regular diagram.
+    pub fn transpose(&self, semitones: i32, use_flats: bool) -> Option<Self> {
+        let name = Chord::parse(&self.name)?
+            .transpose(semitones, use_flats)
+            .to_string();
+        let shift = semitones.rem_euclid(12) as u8;
+
+        if shift != 0 && self.frets.contains(&Some(0)) {
+            return None;
+        }
+
+        let mut base_fret = u16::from(self.base_fret) + u16::from(shift);
+        let span = self
+            .frets
+            .iter()
+            .flatten()
+            .max()
+            .map_or(0, |&f| u16::from(f).max(1) - 1);
+        if shift != 0 && base_fret + span > NECK_FRETS && base_fret > 12 {
+            base_fret -= 12;
+        }
+        let base_fret = u8::try_from(base_fret).ok()?;
+
+        let copy = match &self.copy {
+            Some(copy) => Some(
+                Chord::parse(copy)?
+                    .transpose(semitones, use_flats)
+                    .to_string()
+            ),
+            None => None
+        };
+
+        Some(Self {
+            name,
+            base_fret,
+            copy,
+            ..self.clone()
+        })
+    }
+}
+
+impl fmt::Display for ChordDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if let Some(copy) = &self.copy {
+            write!(f, " copy {}", copy)?;
+        }
+        if self.has_frets() {
+            write!(f, " base-fret {} frets", self.base_fret)?;
+            for fret in &self.frets {
+                match fret {
+                    Some(fret) => write!(f, " {}", fret)?,
+                    None => write!(f, " x")?
+                }
+            }
+        }
+        if !self.fingers.is_empty() {
+            write!(f, " fingers")?;
+            for finger in &self.fingers {
+                match finger {
+                    Some(finger) => write!(f, " {}", finger)?,
+                    None => write!(f, " -")?
+                }
+            }
+        }
+        if !self.keys.is_empty() {
+            write!(f, " keys")?;
+            for key in &self.keys {
+                write!(f, " {}", key)?;
+            }
+        }
+        if let Some(display) = &self.display {
+            write!(f, " display {}", display)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_definition() {
+        let def =
+            ChordDefinition::parse("Asus2 base-fret 1 frets x 0 2 2 0 0 fingers - - 1 2 - -")
+                .unwrap();
+        assert_eq!(def.name, "Asus2");
+        assert_eq!(def.base_fret, 1);
+        assert_eq!(
+            def.frets,
+            vec![None, Some(0), Some(2), Some(2), Some(0), Some(0)]
+        );
+        assert_eq!(def.fingers, vec![None, None, Some(1), Some(2), None, None]);
+        assert!(def.has_frets());
+    }
+
+    #[test]
+    fn test_parse_keys_and_display() {
+        let def = ChordDefinition::parse("Cmaj7 keys 0 4 7 11 display C Δ").unwrap();
+        assert_eq!(def.keys, vec![0, 4, 7, 11]);
+        assert_eq!(def.display, Some("C Δ".to_string()));
+        assert!(!def.has_frets());
+    }
+
+    #[test]
+    fn test_parse_copy_and_name_only() {
+        let def = ChordDefinition::parse("Bb7 copy A7").unwrap();
+        assert_eq!(def.copy, Some("A7".to_string()));
+
+        let def = ChordDefinition::parse("Am").unwrap();
+        assert_eq!(def.name, "Am");
+        assert!(def.frets.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(ChordDefinition::parse("").is_none());
+        assert!(ChordDefinition::parse("Am base-fret").is_none());
+        assert!(ChordDefinition::parse("Am base-fret 0").is_none());
+        assert!(ChordDefinition::parse("Am frets x y z").is_none());
+        assert!(ChordDefinition::parse("Am 1 x 0 2 2 1 0").is_none());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let source = "F#m base-fret 2 frets 1 3 3 1 1 1 fingers 1 3 4 1 1 1";
+        let def = ChordDefinition::parse(source).unwrap();
+        assert_eq!(def.to_string(), source);
+        assert_eq!(ChordDefinition::parse(&def.to_string()).unwrap(), def);
+
+        let def = ChordDefinition::parse("D/F# frets 2 x 0 2 3 2 fingers 1 0 0 2 4 3").unwrap();
+        assert_eq!(
+            def.to_string(),
+            "D/F# base-fret 1 frets 2 x 0 2 3 2 fingers 1 - - 2 4 3"
+        );
+    }
+
+    #[test]
+    fn test_transpose_closed_shape() {
+        let def = ChordDefinition::parse("F#m base-fret 2 frets 1 3 3 1 1 1").unwrap();
+        let transposed = def.transpose(3, false).unwrap();
+        assert_eq!(transposed.name, "Am");
+        assert_eq!(transposed.base_fret, 5);
+        assert_eq!(transposed.frets, def.frets);
+
+        let transposed = def.transpose(-2, false).unwrap();
+        assert_eq!(transposed.name, "Em");
+        assert_eq!(transposed.base_fret, 12);
+    }
+
+    #[test]
+    fn test_transpose_wraps_base_fret() {
+        let def = ChordDefinition::parse("B base-fret 7 frets 1 1 3 3 3 1").unwrap();
+        let transposed = def.transpose(7, true).unwrap();
+        assert_eq!(transposed.name, "Gb");
+        assert_eq!(transposed.base_fret, 14);
+
+        let def = ChordDefinition::parse("B base-fret 19 frets 1 1 3 3 3 1").unwrap();
+        assert_eq!(def.transpose(7, true).unwrap().base_fret, 14);
+    }
+
+    #[test]
+    fn test_transpose_keeps_base_fret_on_the_neck() {
+        let def = ChordDefinition::parse("C base-fret 15 frets x 1 3 3 3 1").unwrap();
+        assert_eq!(def.transpose(0, false).unwrap().base_fret, 15);
+        assert_eq!(def.transpose(12, false).unwrap().base_fret, 15);
+        assert_eq!(def.transpose(-3, false).unwrap().base_fret, 12);
+
+        let def = ChordDefinition::parse("C base-fret 13 frets x 1 3 3 3 1").unwrap();
+        assert_eq!(def.transpose(2, false).unwrap().base_fret, 15);
+        assert_eq!(def.transpose(10, false).unwrap().base_fret, 11);
+    }
+
+    #[test]
+    fn test_transpose_high_base_fret() {
+        let def = ChordDefinition::parse("C base-fret 250 frets x 1 1 1 1 1").unwrap();
+        assert_eq!(def.transpose(10, false).unwrap().base_fret, 248);
+
+        let def = ChordDefinition::parse("C base-fret 255 frets x 1 1 1 1 1").unwrap();
+        assert_eq!(def.transpose(11, false).unwrap().base_fret, 254);
+    }
+
+    #[test]
+    fn test_transpose_open_shape_is_invalidated() {
+        let def = ChordDefinition::parse("Asus2 base-fret 1 frets x 0 2 2 0 0").unwrap();
+        assert!(def.transpose(2, false).is_none());
+        assert_eq!(def.transpose(12, false).unwrap().name, "Asus2");
+    }
+
+    #[test]
+    fn test_transpose_keyboard_and_copy() {
+        let def = ChordDefinition::parse("C keys 0 4 7").unwrap();
+        let transposed = def.transpose(2, false).unwrap();
+        assert_eq!(transposed.name, "D");
+        assert_eq!(transposed.keys, vec![0, 4, 7]);
+
+        let def = ChordDefinition::parse("Bb7 copy A7").unwrap();
+        assert_eq!(
+            def.transpose(1, false).unwrap().copy,
+            Some("A#7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_transpose_invalid_name() {
+        let def = ChordDefinition::parse("Riff frets 0 2 2 0 0 0").unwrap();
+        assert!(def.transpose(0, false).is_none());
+    }
+}
@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Key detection from chord content
+//!
+//! Used when a song has no `{key}` directive. Every major and minor key is
+//! scored by how well the chords fit its scale, whether the song starts and
+//! ends on the tonic chord, and how many V–I cadences resolve to it.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Chord, Key, Mode, Note, NoteName, ParsedSong, Triad};
+
+/// Weight of the share of diatonic chords
+const FIT_WEIGHT: f32 = 0.6;
+/// Weight of starting on the tonic chord
+const FIRST_WEIGHT: f32 = 0.1;
+/// Weight of ending on the tonic chord
+const LAST_WEIGHT: f32 = 0.15;
+/// Weight of V–I cadences (saturates at two)
+const CADENCE_WEIGHT: f32 = 0.15;
+
+/// Possible key of a song
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct KeyCandidate {
+    pub key:        Key,
+    /// Score from 0 to 1
+    pub confidence: f32
+}
+
+/// Estimate the key of a song, best candidate first
+///
+/// Returns an empty list when the song has no chords.
+pub fn detect_key(song: &ParsedSong) -> Vec<KeyCandidate> {
+    detect_key_from_chords(song.chords())
+}
+
+/// Estimate the key from chords in playing order, best candidate first
+pub fn detect_key_from_chords<'a>(
+    chords: impl IntoIterator<Item = &'a Chord>
+) -> Vec<KeyCandidate> {
+    let chords: Vec<&Chord> = chords.into_iter().collect();
+    if !chords.iter().any(|c| Note::parse(&c.root).is_some()) {
+        return Vec::new();
+    }
+
+    let prefer_flats = chords.iter().filter(|c| is_flat(&c.root)).count()
+        > chords.iter().filter(|c| c.root.contains('#')).count();
+
+    let mut candidates: Vec<KeyCandidate> = (0..12)
+        .flat_map(|semitone| [Mode::Major, Mode::Minor].map(|mode| (semitone, mode)))
+        .map(|(semitone, mode)| {
+            let key = spell_key(Note::from_semitone(semitone), mode, prefer_flats);
+            KeyCandidate {
+                key,
+                confidence: score(&key, &chords)
+            }
+        })
+        .filter(|c| c.confidence > 0.0)
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(a.key.signature().abs().cmp(&b.key.signature().abs()))
+    });
+    candidates
+}
+
+/// Key with the simpler signature, `prefer_flats` breaks ties
+fn spell_key(tonic: Note, mode: Mode, prefer_flats: bool) -> Key {
+    [tonic.to_sharp_string(), tonic.to_flat_string()]
+        .into_iter()
+        .filter_map(NoteName::parse)
+        .map(|name| Key::new(name, mode))
+        .min_by_key(|key| (key.signature().abs(), key.uses_flats() != prefer_flats))
+        .expect("at least one spelling")
+}
+
+fn is_flat(root: &str) -> bool {
+    root.chars().nth(1) == Some('b')
+}
+
+fn score(key: &Key, chords: &[&Chord]) -> f32 {
+    let diatonic = chords.iter().map(|c| fit(key, c)).sum::<f32>() / chords.len() as f32;
+
+    let is_tonic = |chord: &&Chord| degree(key, chord) == Some(0) && fit(key, chord) == 1.0;
+    let first = chords.first().is_some_and(is_tonic);
+    let last = chords.last().is_some_and(is_tonic);
+
+    let cadences = chords
+        .windows(2)
+        .filter(|pair| is_dominant(key, pair[0]) && is_tonic(&pair[1]))
+        .count()
+        .min(2);
+
+    FIT_WEIGHT * diatonic
+        + if first { FIRST_WEIGHT } else { 0.0 }
+        + if last { LAST_WEIGHT } else { 0.0 }
+        + CADENCE_WEIGHT * cadences as f32 / 2.0
+}
+
+/// Semitones of the chord root above the tonic
+fn degree(key: &Key, chord: &Chord) -> Option<u8> {
+    let (root, _) = Note::parse(&chord.root)?;
+    Some((root.to_semitone() + 12 - key.tonic.note().to_semitone()) % 12)
+}
+
+/// Diatonic triads per scale degree; minor also allows the harmonic-minor
+/// major V and diminished vii
+fn diatonic_triads(mode: Mode, degree: u8) -> &'static [Triad] {
+    match (mode, degree) {
+        (Mode::Major, 0 | 5 | 7) => &[Triad::Major],
+        (Mode::Major, 2 | 4 | 9) => &[Triad::Minor],
+        (Mode::Major, 11) => &[Triad::Diminished],
+        (Mode::Minor, 0 | 5) => &[Triad::Minor],
+        (Mode::Minor, 2) => &[Triad::Diminished],
+        (Mode::Minor, 3 | 8 | 10) => &[Triad::Major],
+        (Mode::Minor, 7) => &[Triad::Minor, Triad::Major],
+        (Mode::Minor, 11) => &[Triad::Diminished],
+        _ => &[]
+    }
+}
+
+/// 1 for a diatonic chord, 0.5 for a diatonic root with another quality
+/// (or an unrecognized one), 0 otherwise
+fn fit(key: &Key, chord: &Chord) -> f32 {
+    let Some(degree) = degree(key, chord) else {
+        return 0.0;
+    };
+    let triads = diatonic_triads(key.mode, degree);
+    if triads.is_empty() {
+        return 0.0;
+    }
+
+    match &chord.structure {
+        Some(quality) if quality.suspension.is_some() || quality.is_power_chord() => 1.0,
+        Some(quality) if triads.contains(&quality.triad) => 1.0,
+        _ => 0.5
+    }
+}
+
+/// Major chord on the fifth degree
+fn is_dominant(key: &Key, chord: &Chord) -> bool {
+    degree(key, chord) == Some(7)
+        && chord
+            .structure
+            .as_ref()
+            .is_some_and(|q| q.triad == Triad::Major)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChordProParser;
+
+    fn best(content: &str) -> String {
+        detect_key(&ChordProParser::parse(content))[0]
+            .key
+            .to_string()
+    }
+
+    #[test]
+    fn test_detect_major() {
+        assert_eq!(
+            best("[G]Amazing [C]grace how [G]sweet the [D]sound [D7]that [G]saved"),
+            "G"
+        );
+        assert_eq!(best("[C]One [Am]two [F]three [G7]four [C]five"), "C");
+    }
+
+    #[test]
+    fn test_detect_minor() {
+        assert_eq!(best("[Am]One [Dm]two [E7]three [Am]four"), "Am");
+        assert_eq!(
+            best("[Em]One [C]two [G]three [D]four [B7]five [Em]six"),
+            "Em"
+        );
+    }
+
+    #[test]
+    fn test_detect_flat_key() {
+        assert_eq!(best("[Bb]One [Eb]two [F]three [Bb]four"), "Bb");
+        assert_eq!(best("[Gb]One [Db]two [Gb]three"), "Gb");
+        assert_eq!(best("[F#]One [C#]two [F#]three"), "F#");
+    }
+
+    #[test]
+    fn test_detect_ranked_candidates() {
+        let song = ChordProParser::parse("[C]One [F]two [G]three [C]four");
+        let candidates = detect_key(&song);
+        assert!(candidates.len() > 1);
+        assert_eq!(candidates[0].key.to_string(), "C");
+        assert!(candidates[0].confidence > candidates[1].confidence);
+        assert!(candidates[0].confidence <= 1.0);
+    }
+
+    #[test]
+    fn test_detect_includes_grid_chords() {
+        let song = ChordProParser::parse(
+            "{start_of_grid}\n| D . . . | A . . . | D . . . |\n{end_of_grid}"
+        );
+        assert_eq!(detect_key(&song)[0].key.to_string(), "D");
+    }
+
+    #[test]
+    fn test_detect_without_chords() {
+        assert!(detect_key(&ChordProParser::parse("Just lyrics")).is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Parse diagnostics
+//!
+//! Problems reported while parsing a song, with a severity, a stable code
+//! and the location in the source they point at.
+
+use serde::{Deserialize, Serialize};
+
+/// Diagnostic severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info
+}
+
+/// Machine-readable diagnostic code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticCode {
+    /// Directive is not part of ChordPro
+    UnknownDirective,
+    /// ChordPro directive that the parser does not model
+    UnsupportedDirective,
+    /// `{end_of_*}` without a matching `{start_of_*}`
+    UnmatchedSectionEnd,
+    /// `{start_of_*}` that is never closed
+    UnclosedSection,
+    /// `[X]` that is not a valid chord
+    InvalidChord,
+    /// `[` without closing `]`
+    UnclosedChord,
+    /// `{tempo}` value is not a number
+    InvalidTempo,
+    /// `{capo}` value is not a number
+    InvalidCapo,
+    /// `{define}` or `{chord}` value cannot be parsed
+    InvalidDefinition,
+    /// `{chorus}` before any chorus is defined
+    MissingChorus,
+    /// Plain-text line that is neither chords, lyrics nor a heading
+    UnclassifiedLine
+}
+
+/// Location of a diagnostic in the source
+///
+/// Lines and columns are 1-based and counted in characters, `end_column` is
+/// exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Span {
+    pub line:       usize,
+    pub column:     usize,
+    pub end_column: usize
+}
+
+impl Span {
+    /// Span covering `len` characters starting at `column`
+    pub fn new(line: usize, column: usize, len: usize) -> Self {
+        Self {
+            line,
+            column,
+            end_column: column + len
+        }
+    }
+}
+
+/// Problem found while parsing a song
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code:     DiagnosticCode,
+    pub message:  String,
+    pub span:     Span
+}
+
+impl Diagnostic {
+    pub fn error(code: DiagnosticCode, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            span
+        }
+    }
+
+    pub fn warning(code: DiagnosticCode, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code,
+            message: message.into(),
+            span
+        }
+    }
+
+    pub fn info(code: DiagnosticCode, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Info,
+            code,
+            message: message.into(),
+            span
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_new() {
+        let span = Span::new(3, 5, 4);
+        assert_eq!(span.line, 3);
+        assert_eq!(span.column, 5);
+        assert_eq!(span.end_column, 9);
+    }
+
+    #[test]
+    fn test_constructors_set_severity() {
+        let span = Span::new(1, 1, 1);
+        assert_eq!(
+            Diagnostic::error(DiagnosticCode::InvalidChord, span, "x").severity,
+            Severity::Error
+        );
+        assert_eq!(
+            Diagnostic::warning(DiagnosticCode::UnknownDirective, span, "x").severity,
+            Severity::Warning
+        );
+        assert_eq!(
+            Diagnostic::info(DiagnosticCode::UnsupportedDirective, span, "x").severity,
+            Severity::Info
+        );
+    }
+}
@@ -0,0 +1,210 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Chord grids from `{start_of_grid}` sections
+//!
+//! Syntax reference: <https://www.chordpro.org/chordpro/directives-env_grid/>
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::Chord;
+
+/// Whitespace-separated grid token
+static TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\S+").unwrap());
+
+/// Chord grid (bars of beats with chords)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordGrid {
+    pub rows: Vec<GridRow>
+}
+
+/// Single grid line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct GridRow {
+    /// Text before the first bar line
+    pub margin:  Option<String>,
+    pub bars:    Vec<GridBar>,
+    /// Text after the last bar line
+    pub comment: Option<String>
+}
+
+/// Bar (measure) of a grid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct GridBar {
+    /// One cell per beat
+    pub cells: Vec<GridCell>
+}
+
+/// Grid cell (beat)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum GridCell {
+    /// One or more chords, `C~G` splits the beat
+    Chords(Vec<Chord>),
+    /// `.` or `/`, previous chord continues
+    Continue,
+    /// `%`, repeat previous bar
+    RepeatBar,
+    /// `%%`, repeat previous two bars
+    RepeatTwoBars,
+    /// Token that is not a chord
+    Text(String)
+}
+
+impl ChordGrid {
+    /// Parse grid lines; lines without bar lines are skipped
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            rows: lines.into_iter().filter_map(GridRow::parse).collect()
+        }
+    }
+}
+
+impl GridRow {
+    /// Parse a single line like `| C . . . | G . . . |`
+    pub fn parse(line: &str) -> Option<Self> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let first = tokens.iter().position(|t| Self::is_bar_line(t))?;
+        let last = tokens.iter().rposition(|t| Self::is_bar_line(t))?;
+
+        let join = |part: &[&str]| (!part.is_empty()).then(|| part.join(" "));
+
+        let mut bars = Vec::new();
+        let mut cells = Vec::new();
+        for token in &tokens[first + 1..=last] {
+            if Self::is_bar_line(token) {
+                if !cells.is_empty() {
+                    bars.push(GridBar {
+                        cells: std::mem::take(&mut cells)
+                    });
+                }
+            } else {
+                cells.push(GridCell::parse(token));
+            }
+        }
+
+        Some(Self {
+            margin: join(&tokens[..first]),
+            bars,
+            comment: join(&tokens[last + 1..])
+        })
+    }
+
+    /// Rewrite chords between the bar lines of a grid line, keeping spacing,
+    /// margin and comment as they are
+    pub fn map_chords(line: &str, mut f: impl FnMut(&Chord) -> Chord) -> String {
+        let tokens: Vec<_> = TOKEN_RE.find_iter(line).collect();
+        let Some(first) = tokens.iter().position(|t| Self::is_bar_line(t.as_str())) else {
+            return line.to_string();
+        };
+        let last = tokens
+            .iter()
+            .rposition(|t| Self::is_bar_line(t.as_str()))
+            .unwrap_or(first);
+
+        let mut result = String::with_capacity(line.len());
+        let mut end = 0;
+        for token in &tokens[first..=last] {
+            if let GridCell::Chords(chords) = GridCell::parse(token.as_str()) {
+                result.push_str(&line[end..token.start()]);
+                let mapped: Vec<String> = chords.iter().map(|c| f(c).to_string()).collect();
+                result.push_str(&mapped.join("~"));
+                end = token.end();
+            }
+        }
+        result.push_str(&line[end..]);
+        result
+    }
+
+    /// `|`, `||`, `|.`, `|:`, `:|`, `:|:` and volta bars like `|2`
+    fn is_bar_line(token: &str) -> bool {
+        token.contains('|')
+            && token
+                .chars()
+                .all(|c| matches!(c, '|' | ':' | '.') || c.is_ascii_digit())
+    }
+}
+
+impl GridCell {
+    /// Parse a single cell token
+    pub fn parse(token: &str) -> Self {
+        match token {
+            "." | "/" => Self::Continue,
+            "%" => Self::RepeatBar,
+            "%%" => Self::RepeatTwoBars,
+            _ => token
+                .split('~')
+                .map(Chord::parse)
+                .collect::<Option<Vec<_>>>()
+                .map_or_else(|| Self::Text(token.to_string()), Self::Chords)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_row() {
+        let row = GridRow::parse("Intro || G . . . | C~D . / . :| x2").unwrap();
+        assert_eq!(row.margin, Some("Intro".to_string()));
+        assert_eq!(row.comment, Some("x2".to_string()));
+        assert_eq!(row.bars.len(), 2);
+        assert_eq!(row.bars[0].cells.len(), 4);
+        assert_eq!(
+            row.bars[0].cells[0],
+            GridCell::Chords(vec![Chord::parse("G").unwrap()])
+        );
+        assert_eq!(row.bars[0].cells[1], GridCell::Continue);
+        assert_eq!(
+            row.bars[1].cells[0],
+            GridCell::Chords(vec![Chord::parse("C").unwrap(), Chord::parse("D").unwrap()])
+        );
+        assert_eq!(row.bars[1].cells[2], GridCell::Continue);
+    }
+
+    #[test]
+    fn test_parse_repeats_and_text() {
+        let row = GridRow::parse("|: Am . | % | %% | N.C. :|2").unwrap();
+        assert_eq!(row.bars.len(), 4);
+        assert_eq!(row.bars[1].cells, vec![GridCell::RepeatBar]);
+        assert_eq!(row.bars[2].cells, vec![GridCell::RepeatTwoBars]);
+        assert_eq!(row.bars[3].cells, vec![GridCell::Text("N.C.".to_string())]);
+        assert!(row.margin.is_none());
+        assert!(row.comment.is_none());
+    }
+
+    #[test]
+    fn test_parse_grid_skips_lines_without_bars() {
+        let grid = ChordGrid::parse(["Just text", "| D . | G . |", ""]);
+        assert_eq!(grid.rows.len(), 1);
+        assert_eq!(grid.rows[0].bars.len(), 2);
+    }
+
+    #[test]
+    fn test_map_chords_keeps_layout() {
+        let line = "A  || G .  C~D . | Em . . . |  x2";
+        let mapped = GridRow::map_chords(line, |c| c.transpose(2, false));
+        assert_eq!(mapped, "A  || A .  D~E . | F#m . . . |  x2");
+        assert_eq!(GridRow::map_chords("no bars", |c| c.clone()), "no bars");
+    }
+
+    #[test]
+    fn test_bar_line_detection() {
+        assert!(GridRow::is_bar_line("|"));
+        assert!(GridRow::is_bar_line("||"));
+        assert!(GridRow::is_bar_line("|."));
+        assert!(GridRow::is_bar_line(":|:"));
+        assert!(GridRow::is_bar_line("|2"));
+        assert!(!GridRow::is_bar_line("."));
+        assert!(!GridRow::is_bar_line("G|"));
+    }
+}
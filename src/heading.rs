@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Section headings written as text
+//!
+//! Plain text imports and the compatible ChordPro dialect recognize the same
+//! English and Russian headings: `Verse 1:`, `[Chorus]`, `Припев:` or
+//! `Вступление: Am F`.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Section heading with an optional number, brackets, colon and the rest of
+/// the line
+static HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^[\[(]?\s*(куплет|запев|припев|рефрен|предприпев|пре-припев|бридж|мост|вступление|интро|проигрыш|кода|окончание|концовка|verse|chorus|refrain|pre-chorus|prechorus|bridge|intro|outro|interlude|instrumental|tag|ending|coda)\s*(\d+)?\s*[\])]?\s*(:)?\s*(.*)$"
+    )
+    .unwrap()
+});
+
+/// Heading at the start of a line
+pub(crate) struct Heading<'a> {
+    /// Heading word as written, like `Припев`
+    pub word:   &'a str,
+    pub number: Option<&'a str>,
+    /// Whether a colon follows the heading
+    pub colon:  bool,
+    /// Text after the heading, like the chords of an intro
+    pub rest:   &'a str
+}
+
+impl<'a> Heading<'a> {
+    /// Heading at the start of a trimmed line
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let caps = HEADING_RE.captures(line)?;
+        Some(Self {
+            word:   caps.get(1)?.as_str(),
+            number: caps.get(2).map(|m| m.as_str()),
+            colon:  caps.get(3).is_some(),
+            rest:   caps.get(4).map_or("", |m| m.as_str())
+        })
+    }
+
+    /// ChordPro section kind, like `chorus` or `prechorus`
+    pub fn kind(&self) -> &'static str {
+        match self.word.to_lowercase().as_str() {
+            "припев" | "рефрен" | "chorus" | "refrain" => "chorus",
+            "предприпев" | "пре-припев" | "pre-chorus" | "prechorus" => {
+                "prechorus"
+            }
+            "бридж" | "мост" | "bridge" => "bridge",
+            "вступление" | "интро" | "intro" => "intro",
+            "проигрыш" | "interlude" | "instrumental" => "interlude",
+            "окончание" | "outro" => "outro",
+            "кода" | "концовка" | "ending" | "coda" => "ending",
+            "tag" => "tag",
+            _ => "verse"
+        }
+    }
+
+    /// Capitalized label like `Куплет 1`
+    pub fn label(&self) -> String {
+        let mut chars = self.word.chars();
+        let word: String = match chars.next() {
+            Some(first) => first
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect(),
+            None => String::new()
+        };
+
+        match self.number {
+            Some(number) => format!("{} {}", word, number),
+            None => word
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_heading() {
+        let heading = Heading::parse("ПРИПЕВ 2:").unwrap();
+        assert_eq!(heading.kind(), "chorus");
+        assert_eq!(heading.label(), "Припев 2");
+        assert!(heading.colon);
+
+        let heading = Heading::parse("[Pre-Chorus]").unwrap();
+        assert_eq!(heading.kind(), "prechorus");
+        assert!(!heading.colon);
+
+        let heading = Heading::parse("Вступление: Am F").unwrap();
+        assert_eq!(heading.kind(), "intro");
+        assert_eq!(heading.rest, "Am F");
+
+        assert!(Heading::parse("Amazing grace").is_none());
+    }
+}
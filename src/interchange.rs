@@ -0,0 +1,280 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Songs exchanged with other worship software
+//!
+//! Importers and exporters for foreign formats share one model: song
+//! metadata as a [`CreateSong`], the lyrics as a [`ParsedSong`] and the
+//! details our schema does not store, like the CCLI number or lyrics in
+//! other languages.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ChordProParser, ChordProWriter, CreateSong, ParsedSong, Song, SongCategory, SongSection,
+    SongSectionType
+};
+
+/// Song read from or written to an interchange format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct InterchangeSong {
+    /// Metadata and ChordPro content ready to be stored
+    pub song:                CreateSong,
+    /// Structured lyrics of `song.content`
+    pub parsed:              ParsedSong,
+    /// Language of the main lyrics, like `en` or `ru`
+    pub language:            Option<String>,
+    /// Language the translator translated into
+    pub translator_language: Option<String>,
+    pub ccli_number:         Option<String>,
+    /// Themes as written in the source, including ones without a category
+    pub themes:              Vec<String>,
+    /// Lyrics in other languages
+    pub translations:        Vec<SongTranslation>
+}
+
+/// Lyrics of a song in another language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct SongTranslation {
+    pub language: String,
+    pub title:    Option<String>,
+    pub parsed:   ParsedSong
+}
+
+impl InterchangeSong {
+    /// Song with `song.content` written from `parsed`
+    pub fn new(mut song: CreateSong, parsed: ParsedSong) -> Self {
+        song.content = ChordProWriter::default().write(&parsed);
+        Self {
+            song,
+            parsed,
+            language: None,
+            translator_language: None,
+            ccli_number: None,
+            themes: Vec::new(),
+            translations: Vec::new()
+        }
+    }
+
+    /// Stored song prepared for export; categories and tags become themes
+    pub fn from_song(song: &Song) -> Self {
+        let themes = song
+            .categories
+            .iter()
+            .map(|category| category.name_en().to_string())
+            .chain(song.tags.iter().map(|tag| tag.name.clone()))
+            .collect();
+
+        Self {
+            song: CreateSong {
+                songbook_id:    song.songbook_id,
+                number:         song.number,
+                title:          song.title.clone(),
+                title_alt:      song.title_alt.clone(),
+                author_lyrics:  song.author_lyrics.clone(),
+                author_music:   song.author_music.clone(),
+                translator:     song.translator.clone(),
+                year_written:   song.year_written,
+                copyright:      song.copyright.clone(),
+                original_key:   song.original_key.clone(),
+                tempo:          song.tempo,
+                time_signature: song.time_signature.clone(),
+                content:        song.content.clone(),
+                categories:     song.categories.clone(),
+                tag_ids:        song.tags.iter().map(|tag| tag.id).collect(),
+                source_url:     None
+            },
+            parsed: ChordProParser::parse(&song.content),
+            language: None,
+            translator_language: None,
+            ccli_number: None,
+            themes,
+            translations: Vec::new()
+        }
+    }
+}
+
+/// Categories matching theme names, without duplicates
+pub(crate) fn theme_categories(themes: &[String]) -> Vec<SongCategory> {
+    let mut categories = Vec::new();
+    for category in themes
+        .iter()
+        .filter_map(|theme| SongCategory::from_name(theme))
+    {
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    categories
+}
+
+/// Section defined once under a name like `v1` or `C`
+pub(crate) struct NamedSection {
+    pub name:    String,
+    pub section: SongSection
+}
+
+/// Sections in performance order, falling back to definition order
+///
+/// Names are matched ignoring case. A repeat of the most recent chorus
+/// becomes a `{chorus}` recall, other repeats are copied.
+pub(crate) fn arrange(defined: &[NamedSection], order: &[String]) -> Vec<SongSection> {
+    let find = |name: &str| {
+        defined
+            .iter()
+            .position(|named| named.name.eq_ignore_ascii_case(name))
+    };
+    let mut order: Vec<usize> = order.iter().filter_map(|name| find(name)).collect();
+    if order.is_empty() {
+        order = (0..defined.len()).collect();
+    }
+
+    let mut sections: Vec<SongSection> = Vec::new();
+    let mut placed: Vec<(usize, usize)> = Vec::new();
+
+    for index in order {
+        let section = &defined[index].section;
+        let previous = placed
+            .iter()
+            .find(|(placed, _)| *placed == index)
+            .map(|(_, at)| *at);
+        let last_chorus = sections
+            .iter()
+            .rposition(|s| s.section_type == SongSectionType::Chorus && s.recall.is_none());
+
+        if section.section_type == SongSectionType::Chorus
+            && previous.is_some()
+            && previous == last_chorus
+        {
+            sections.push(SongSection {
+                lines: Vec::new(),
+                recall: previous,
+                ..section.clone()
+            });
+            continue;
+        }
+
+        if previous.is_none() {
+            placed.push((index, sections.len()));
+        }
+        sections.push(section.clone());
+    }
+    sections
+}
+
+/// Name like `v1` for each section, numbered per letter; recalls repeat the
+/// name of their chorus, verbatim sections have none
+pub(crate) fn section_names(
+    sections: &[SongSection],
+    letter: impl Fn(SongSectionType) -> char
+) -> Vec<Option<String>> {
+    let mut names: Vec<Option<String>> = Vec::new();
+    let mut counts: Vec<(char, u32)> = Vec::new();
+
+    for section in sections {
+        if let Some(index) = section.recall {
+            names.push(names.get(index).cloned().flatten());
+            continue;
+        }
+        if section.section_type.is_verbatim() {
+            names.push(None);
+            continue;
+        }
+
+        let letter = letter(section.section_type);
+        let count = match counts.iter_mut().find(|(l, _)| *l == letter) {
+            Some((_, count)) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                counts.push((letter, 1));
+                1
+            }
+        };
+        names.push(Some(format!("{}{}", letter, count)));
+    }
+    names
+}
+
+/// Song with only a title and sections
+pub(crate) fn lyrics_song(title: Option<String>, sections: Vec<SongSection>) -> ParsedSong {
+    ParsedSong {
+        title,
+        subtitle: None,
+        artist: None,
+        composer: None,
+        key: None,
+        tempo: None,
+        time_signature: None,
+        capo: None,
+        definitions: Vec::new(),
+        sections
+    }
+}
+
+/// Escape text for XML content and attribute values
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Error reading a song from an interchange format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    /// Input is not well-formed XML
+    Xml(String),
+    /// Document root is not the element the format requires
+    UnexpectedRoot(String),
+    /// Required element is missing or empty
+    MissingElement(&'static str)
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xml(message) => write!(f, "invalid XML: {}", message),
+            Self::UnexpectedRoot(name) => write!(f, "unexpected root element <{}>", name),
+            Self::MissingElement(name) => write!(f, "missing <{}>", name)
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_categories() {
+        let themes = vec![
+            "Christmas".to_string(),
+            "Jazz".to_string(),
+            "Рождественские".to_string(),
+            "Holy Spirit".to_string(),
+        ];
+        assert_eq!(
+            theme_categories(&themes),
+            vec![SongCategory::Christmas, SongCategory::HolySpirit]
+        );
+    }
+
+    #[test]
+    fn test_import_error_display() {
+        assert_eq!(
+            ImportError::MissingElement("title").to_string(),
+            "missing <title>"
+        );
+        assert_eq!(
+            ImportError::UnexpectedRoot("html".into()).to_string(),
+            "unexpected root element <html>"
+        );
+    }
+}
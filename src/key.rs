@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Musical keys and key-signature-aware spelling
+//!
+//! Notes are spelled by the key signature: in F major the fourth is `Bb`,
+//! not `A#`; in Eb minor the third is `Gb`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Interval, Letter, Note, NoteName, NoteNaming};
+
+/// Major or minor mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    #[default]
+    Major,
+    Minor
+}
+
+impl Mode {
+    /// Semitones of the scale degrees (natural minor for minor)
+    fn scale(self) -> [u8; 7] {
+        match self {
+            Self::Major => [0, 2, 4, 5, 7, 9, 11],
+            Self::Minor => [0, 2, 3, 5, 7, 8, 10]
+        }
+    }
+}
+
+/// Musical key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Key {
+    pub tonic: NoteName,
+    pub mode:  Mode
+}
+
+impl Key {
+    pub fn new(tonic: NoteName, mode: Mode) -> Self {
+        Self {
+            tonic,
+            mode
+        }
+    }
+
+    /// Parse key like `G`, `F#m`, `Bb`, `Ebm`, `A minor`, `C major`
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::parse_with(s, NoteNaming::English)
+    }
+
+    /// Parse key written in the given naming system (`H`, `Dom`, `Ля minor`)
+    pub fn parse_with(s: &str, naming: NoteNaming) -> Option<Self> {
+        let (tonic, rest) = naming.parse_prefix(s.trim())?;
+
+        let mode = match rest.trim().to_lowercase().as_str() {
+            "" | "maj" | "major" | "dur" => Mode::Major,
+            "m" | "mi" | "min" | "minor" | "moll" | "-" => Mode::Minor,
+            _ => return None
+        };
+
+        Some(Self::new(tonic, mode))
+    }
+
+    /// Key written in the given naming system
+    pub fn to_string_with(&self, naming: NoteNaming) -> String {
+        match self.mode {
+            Mode::Major => naming.format(self.tonic),
+            Mode::Minor => format!("{}m", naming.format(self.tonic))
+        }
+    }
+
+    /// Key signature: number of sharps (positive) or flats (negative)
+    pub fn signature(&self) -> i8 {
+        let fifths = match self.tonic.letter {
+            Letter::F => -1,
+            Letter::C => 0,
+            Letter::G => 1,
+            Letter::D => 2,
+            Letter::A => 3,
+            Letter::E => 4,
+            Letter::B => 5
+        };
+        let major = fifths + 7 * self.tonic.shift;
+        match self.mode {
+            Mode::Major => major,
+            Mode::Minor => major - 3
+        }
+    }
+
+    /// Relative major or minor key
+    pub fn relative(&self) -> Self {
+        match self.mode {
+            Mode::Major => Self::new(self.tonic.above(Interval::new(6, 9)), Mode::Minor),
+            Mode::Minor => Self::new(self.tonic.above(Interval::new(3, 3)), Mode::Major)
+        }
+    }
+
+    /// Spelled notes of the scale
+    pub fn scale(&self) -> Vec<NoteName> {
+        self.mode
+            .scale()
+            .iter()
+            .zip(1..)
+            .map(|(&semitones, degree)| self.tonic.above(Interval::new(degree, semitones)))
+            .collect()
+    }
+
+    /// Spell a pitch class in this key
+    ///
+    /// Scale notes use the scale spelling, other notes use sharps in sharp
+    /// keys and flats in flat keys.
+    pub fn spell(&self, note: Note) -> NoteName {
+        if let Some(name) = self.scale().into_iter().find(|n| n.note() == note) {
+            return name;
+        }
+
+        let spelled = if self.uses_flats() {
+            note.to_flat_string()
+        } else {
+            note.to_sharp_string()
+        };
+        NoteName::parse(spelled).expect("note names are valid")
+    }
+
+    /// Written note respelled for this key
+    ///
+    /// Scale notes and the raised seventh of minor keys take the key's
+    /// spelling, and sharps in flat keys become flats, so `A#` written by
+    /// sharp-only transposition reads as `Bb` in Bb. Other notes keep their
+    /// letter.
+    pub fn respell(&self, name: NoteName) -> NoteName {
+        let note = name.note();
+        let leading = (self.mode == Mode::Minor).then(|| self.tonic.above(Interval::new(7, 11)));
+        if let Some(spelled) = self
+            .scale()
+            .into_iter()
+            .chain(leading)
+            .find(|n| n.note() == note)
+        {
+            return spelled;
+        }
+
+        if self.uses_flats() && name.shift > 0 {
+            self.spell(note)
+        } else {
+            name
+        }
+    }
+
+    /// Whether the key signature has flats
+    pub fn uses_flats(&self) -> bool {
+        self.signature() < 0
+    }
+
+    /// Transpose the key, choosing the spelling with the simpler signature
+    ///
+    /// On a tie (F# / Gb major) the side of the original key is kept.
+    pub fn transpose(&self, semitones: i32) -> Self {
+        let note = self.tonic.note().transpose(semitones);
+
+        [note.to_sharp_string(), note.to_flat_string()]
+            .into_iter()
+            .filter_map(NoteName::parse)
+            .map(|tonic| Self::new(tonic, self.mode))
+            .min_by_key(|key| {
+                (
+                    key.signature().unsigned_abs(),
+                    key.uses_flats() != self.uses_flats()
+                )
+            })
+            .expect("at least one spelling")
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            Mode::Major => write!(f, "{}", self.tonic),
+            Mode::Minor => write!(f, "{}m", self.tonic)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        Key::parse(s).unwrap()
+    }
+
+    fn spell(key: &Key, notes: &[Note]) -> Vec<String> {
+        notes.iter().map(|n| key.spell(*n).to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            key("G"),
+            Key::new(NoteName::parse("G").unwrap(), Mode::Major)
+        );
+        assert_eq!(key("F#m").mode, Mode::Minor);
+        assert_eq!(key("F#m").tonic.shift, 1);
+        assert_eq!(key("Bbm").tonic.to_string(), "Bb");
+        assert_eq!(key("A minor"), key("Am"));
+        assert_eq!(key("C major"), key("C"));
+        assert!(Key::parse("").is_none());
+        assert!(Key::parse("X").is_none());
+        assert!(Key::parse("C lydian").is_none());
+    }
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(key("C").signature(), 0);
+        assert_eq!(key("G").signature(), 1);
+        assert_eq!(key("F").signature(), -1);
+        assert_eq!(key("Bb").signature(), -2);
+        assert_eq!(key("F#").signature(), 6);
+        assert_eq!(key("Gb").signature(), -6);
+        assert_eq!(key("Am").signature(), 0);
+        assert_eq!(key("Em").signature(), 1);
+        assert_eq!(key("Ebm").signature(), -6);
+        assert_eq!(key("C#m").signature(), 4);
+    }
+
+    #[test]
+    fn test_relative() {
+        assert_eq!(key("C").relative(), key("Am"));
+        assert_eq!(key("Am").relative(), key("C"));
+        assert_eq!(key("Eb").relative(), key("Cm"));
+        assert_eq!(key("F#m").relative(), key("A"));
+    }
+
+    #[test]
+    fn test_scale() {
+        let scale: Vec<String> = key("Gb").scale().iter().map(ToString::to_string).collect();
+        assert_eq!(scale, ["Gb", "Ab", "Bb", "Cb", "Db", "Eb", "F"]);
+
+        let scale: Vec<String> = key("Ebm").scale().iter().map(ToString::to_string).collect();
+        assert_eq!(scale, ["Eb", "F", "Gb", "Ab", "Bb", "Cb", "Db"]);
+    }
+
+    #[test]
+    fn test_spell() {
+        assert_eq!(spell(&key("F"), &[Note::ASharp]), ["Bb"]);
+        assert_eq!(spell(&key("Ebm"), &[Note::FSharp, Note::B]), ["Gb", "Cb"]);
+        assert_eq!(
+            spell(&key("E"), &[Note::GSharp, Note::DSharp]),
+            ["G#", "D#"]
+        );
+        assert_eq!(spell(&key("D"), &[Note::ASharp]), ["A#"]);
+        assert_eq!(spell(&key("Bb"), &[Note::FSharp]), ["Gb"]);
+    }
+
+    #[test]
+    fn test_respell() {
+        let respell =
+            |key: &Key, note: &str| key.respell(NoteName::parse(note).unwrap()).to_string();
+        assert_eq!(respell(&key("Bb"), "A#"), "Bb");
+        assert_eq!(respell(&key("Bb"), "D#"), "Eb");
+        assert_eq!(respell(&key("Bb"), "G#"), "Ab");
+        assert_eq!(respell(&key("Dm"), "C#"), "C#");
+        assert_eq!(respell(&key("C"), "Bb"), "Bb");
+        assert_eq!(respell(&key("C"), "F#"), "F#");
+        assert_eq!(respell(&key("E"), "Ab"), "G#");
+    }
+
+    #[test]
+    fn test_transpose() {
+        assert_eq!(key("C").transpose(5), key("F"));
+        assert_eq!(key("C").transpose(3), key("Eb"));
+        assert_eq!(key("C").transpose(1), key("Db"));
+        assert_eq!(key("A").transpose(2), key("B"));
+        assert_eq!(key("Am").transpose(1), key("Bbm"));
+        assert_eq!(key("Fm").transpose(-2), key("Ebm"));
+        assert_eq!(key("Em").transpose(-1), key("D#m"));
+        assert_eq!(key("E").transpose(2), key("F#"));
+        assert_eq!(key("Eb").transpose(3), key("Gb"));
+        assert_eq!(key("C").transpose(12), key("C"));
+    }
+
+    #[test]
+    fn test_naming() {
+        assert_eq!(Key::parse_with("B", NoteNaming::German), Some(key("Bb")));
+        assert_eq!(Key::parse_with("Hm", NoteNaming::German), Some(key("Bm")));
+        assert_eq!(
+            Key::parse_with("Ля minor", NoteNaming::SolfegeCyrillic),
+            Some(key("Am"))
+        );
+        assert_eq!(key("Bb").to_string_with(NoteNaming::German), "B");
+        assert_eq!(key("F#m").to_string_with(NoteNaming::Solfege), "Fa#m");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(key("Bb").to_string(), "Bb");
+        assert_eq!(key("F# minor").to_string(), "F#m");
+    }
+}
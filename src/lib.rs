@@ -1,37 +1,82 @@
 // SPDX-FileCopyrightText: 2025 Revelation Team
 // SPDX-License-Identifier: MIT
 
+mod analysis;
+mod capo;
 mod category;
 mod chord;
+mod definition;
+mod detect;
+mod diagnostic;
 mod edition;
 mod entity;
 mod filters;
+mod grid;
+mod heading;
 mod history;
+mod interchange;
+mod key;
+mod naming;
+mod nashville;
+mod normalize;
 mod note;
+mod openlyrics;
+mod opensong;
 mod parsed;
 mod parser;
+mod pitch;
+mod plaintext;
 mod playlist;
+mod quality;
 mod search;
 mod section;
+mod simplify;
 mod song;
+mod svg;
+mod syntax;
 mod tag;
 mod transpose;
+mod vocal;
+mod voicing;
+mod writer;
 
 #[cfg(feature = "backend")]
 pub mod ports;
 
+pub use analysis::*;
+pub use capo::*;
 pub use category::*;
 pub use chord::*;
+pub use definition::*;
+pub use detect::*;
+pub use diagnostic::*;
 pub use edition::*;
 pub use entity::*;
 pub use filters::*;
+pub use grid::*;
 pub use history::*;
+pub use interchange::*;
+pub use key::*;
+pub use naming::*;
+pub use nashville::*;
+pub use normalize::*;
 pub use note::*;
+pub use openlyrics::*;
+pub use opensong::*;
 pub use parsed::*;
 pub use parser::*;
+pub use pitch::*;
+pub use plaintext::*;
 pub use playlist::*;
+pub use quality::*;
 pub use search::*;
 pub use section::*;
+pub use simplify::*;
 pub use song::*;
+pub use svg::*;
+pub use syntax::*;
 pub use tag::*;
 pub use transpose::*;
+pub use vocal::*;
+pub use voicing::*;
+pub use writer::*;
@@ -0,0 +1,245 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Note naming systems
+//!
+//! Chords are stored with English note names. Other systems are used for
+//! input and display: German (`H` for B, `B` for Bb) and fixed-do solfège
+//! in Latin (`Do`, `Re`, `Mi`) or Cyrillic (`До`, `Ре`, `Ми`) script.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Chord, Key, Letter, NoteName, SyntaxTree};
+
+/// Fixed-do syllables in Latin script, in letter order C..B
+const SOLFEGE: [&str; 7] = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Si"];
+
+/// Fixed-do syllables in Cyrillic script, in letter order C..B
+const SOLFEGE_CYRILLIC: [&str; 7] = ["До", "Ре", "Ми", "Фа", "Соль", "Ля", "Си"];
+
+/// Note naming system
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NoteNaming {
+    /// C D E F G A B, `H` accepted as B
+    #[default]
+    English,
+    /// C D E F G A H, `B` is B flat
+    German,
+    /// Do Re Mi Fa Sol La Si
+    Solfege,
+    /// До Ре Ми Фа Соль Ля Си
+    SolfegeCyrillic
+}
+
+impl NoteNaming {
+    /// Parse a whole note name like `F#`, `H`, `Sib`, `Фа#`
+    pub fn parse_note(self, s: &str) -> Option<NoteName> {
+        match self.parse_prefix(s.trim())? {
+            (note, "") => Some(note),
+            _ => None
+        }
+    }
+
+    /// Parse a note at the start of `s` (at most one accidental) and return
+    /// the rest
+    pub fn parse_prefix(self, s: &str) -> Option<(NoteName, &str)> {
+        let (letter, mut shift, rest) = match self {
+            Self::English | Self::German => {
+                let first = s.chars().next()?;
+                let rest = &s[first.len_utf8()..];
+                match (self, latin_lookalike(first).to_ascii_uppercase()) {
+                    (Self::German, 'B') => (Letter::B, -1, rest),
+                    (_, 'H') => (Letter::B, 0, rest),
+                    (_, c) => (Letter::from_char(c)?, 0, rest)
+                }
+            }
+            Self::Solfege | Self::SolfegeCyrillic => {
+                let syllables = if self == Self::Solfege {
+                    &SOLFEGE
+                } else {
+                    &SOLFEGE_CYRILLIC
+                };
+                syllables
+                    .iter()
+                    .zip(Letter::ALL)
+                    .find_map(|(syllable, letter)| {
+                        let len = syllable.len();
+                        s.get(..len)
+                            .filter(|head| head.to_lowercase() == syllable.to_lowercase())
+                            .map(|_| (letter, 0, &s[len..]))
+                    })?
+            }
+        };
+
+        let rest = match rest.chars().next() {
+            Some(c @ ('#' | '♯' | 'b' | '♭')) => {
+                shift += if matches!(c, '#' | '♯') { 1 } else { -1 };
+                &rest[c.len_utf8()..]
+            }
+            _ => rest
+        };
+
+        Some((
+            NoteName {
+                letter,
+                shift
+            },
+            rest
+        ))
+    }
+
+    /// Note name in this system
+    pub fn format(self, note: NoteName) -> String {
+        let accidentals = |shift: i8| {
+            let accidental = if shift > 0 { "#" } else { "b" };
+            accidental.repeat(shift.unsigned_abs() as usize)
+        };
+        let index = note.letter.index() as usize;
+
+        match self {
+            Self::English => note.to_string(),
+            Self::German if note.letter == Letter::B => match note.shift {
+                shift if shift < 0 => format!("B{}", accidentals(shift + 1)),
+                shift => format!("H{}", accidentals(shift))
+            },
+            Self::German => note.to_string(),
+            Self::Solfege => format!("{}{}", SOLFEGE[index], accidentals(note.shift)),
+            Self::SolfegeCyrillic => {
+                format!("{}{}", SOLFEGE_CYRILLIC[index], accidentals(note.shift))
+            }
+        }
+    }
+}
+
+/// Latin letter for an uppercase Cyrillic look-alike typed by mistake (`С`,
+/// `А`, `Е`, `Н`, `В`)
+///
+/// Lowercase letters are not mapped: `а`, `с` and `е` are Russian words.
+pub(crate) fn latin_lookalike(c: char) -> char {
+    match c {
+        'С' => 'C',
+        'А' => 'A',
+        'Е' => 'E',
+        'Н' => 'H',
+        'В' => 'B',
+        _ => c
+    }
+}
+
+/// Rewrite chords and `{key}` in ChordPro content from one naming to another
+///
+/// Tokens that do not parse in `from` are left as they are.
+pub fn convert_naming(content: &str, from: NoteNaming, to: NoteNaming) -> String {
+    let mut tree = SyntaxTree::parse(content);
+
+    for token in tree.chords_mut() {
+        if let Some(chord) = Chord::parse_with(token, from) {
+            *token = chord.to_string_with(to);
+        }
+    }
+    for directive in tree.directives_mut().filter(|d| d.is("key")) {
+        if let Some(value) = &mut directive.value
+            && let Some(key) = Key::parse_with(value, from)
+        {
+            *value = key.to_string_with(to);
+        }
+    }
+
+    tree.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Note;
+
+    fn parse(naming: NoteNaming, s: &str) -> String {
+        naming.parse_note(s).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_english() {
+        assert_eq!(parse(NoteNaming::English, "B"), "B");
+        assert_eq!(parse(NoteNaming::English, "H"), "B");
+        assert_eq!(parse(NoteNaming::English, "Hb"), "Bb");
+        assert_eq!(parse(NoteNaming::English, "F#"), "F#");
+        assert!(NoteNaming::English.parse_note("X").is_none());
+        assert!(NoteNaming::English.parse_note("C##").is_none());
+    }
+
+    #[test]
+    fn test_parse_german() {
+        assert_eq!(parse(NoteNaming::German, "H"), "B");
+        assert_eq!(parse(NoteNaming::German, "B"), "Bb");
+        assert_eq!(parse(NoteNaming::German, "Hb"), "Bb");
+        assert_eq!(parse(NoteNaming::German, "C#"), "C#");
+    }
+
+    #[test]
+    fn test_parse_solfege() {
+        assert_eq!(parse(NoteNaming::Solfege, "Do"), "C");
+        assert_eq!(parse(NoteNaming::Solfege, "Sol#"), "G#");
+        assert_eq!(parse(NoteNaming::Solfege, "sib"), "Bb");
+        assert_eq!(parse(NoteNaming::SolfegeCyrillic, "Ре"), "D");
+        assert_eq!(parse(NoteNaming::SolfegeCyrillic, "Соль"), "G");
+        assert_eq!(parse(NoteNaming::SolfegeCyrillic, "ля#"), "A#");
+        assert!(NoteNaming::Solfege.parse_note("C").is_none());
+    }
+
+    #[test]
+    fn test_parse_cyrillic_lookalikes() {
+        assert_eq!(parse(NoteNaming::English, "С"), "C");
+        assert_eq!(parse(NoteNaming::English, "А#"), "A#");
+        assert_eq!(parse(NoteNaming::English, "Еb"), "Eb");
+        assert_eq!(parse(NoteNaming::English, "Н"), "B");
+        assert_eq!(parse(NoteNaming::German, "В"), "Bb");
+    }
+
+    #[test]
+    fn test_format() {
+        let name = |s: &str| NoteName::parse(s).unwrap();
+        assert_eq!(NoteNaming::German.format(name("B")), "H");
+        assert_eq!(NoteNaming::German.format(name("Bb")), "B");
+        assert_eq!(NoteNaming::German.format(name("Eb")), "Eb");
+        assert_eq!(NoteNaming::Solfege.format(name("F#")), "Fa#");
+        assert_eq!(NoteNaming::SolfegeCyrillic.format(name("Bb")), "Сиb");
+        assert_eq!(NoteNaming::SolfegeCyrillic.format(name("G")), "Соль");
+    }
+
+    #[test]
+    fn test_format_parse_round_trip() {
+        for naming in [
+            NoteNaming::English,
+            NoteNaming::German,
+            NoteNaming::Solfege,
+            NoteNaming::SolfegeCyrillic
+        ] {
+            for semitone in 0..12 {
+                let note = Note::from_semitone(semitone);
+                for spelled in [note.to_sharp_string(), note.to_flat_string()] {
+                    let name = NoteName::parse(spelled).unwrap();
+                    assert_eq!(naming.parse_note(&naming.format(name)), Some(name));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_naming() {
+        let content = "{key: Bb}\n[Bb]Hello [F/A]world [Gm7]again [*riff]";
+        let german = convert_naming(content, NoteNaming::English, NoteNaming::German);
+        assert_eq!(german, "{key: B}\n[B]Hello [F/A]world [Gm7]again [*riff]");
+        assert_eq!(
+            convert_naming(&german, NoteNaming::German, NoteNaming::English),
+            content
+        );
+
+        let cyrillic = convert_naming(content, NoteNaming::English, NoteNaming::SolfegeCyrillic);
+        assert_eq!(
+            cyrillic,
+            "{key: Сиb}\n[Сиb]Hello [Фа/Ля]world [Сольm7]again [*riff]"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Nashville Number System
+//!
+//! Chords are written as scale degrees of the key: in G, `[C]` is `[4]`,
+//! `[D/F#]` is `[5/7]` and `[Em7]` is `[6m7]`. Degrees are counted on the
+//! major scale of the tonic, so in A minor `[C]` is `[b3]`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Chord, ChordProWriter, ChordQuality, Interval, Key, NoteName, ParsedSong, SyntaxTree
+};
+
+/// Scale degree with an optional accidental, like `4`, `b7` or `#4`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ScaleDegree {
+    /// Degree from 1 to 7
+    pub degree: u8,
+    /// Semitones from the major scale degree: -1 = flat, 1 = sharp
+    pub shift:  i8
+}
+
+impl ScaleDegree {
+    /// Parse degree like `5`, `b3`, `#4`
+    pub fn parse(s: &str) -> Option<Self> {
+        let (degree, rest) = Self::parse_prefix(s)?;
+        rest.is_empty().then_some(degree)
+    }
+
+    /// Parse a leading degree and return the rest of the string
+    fn parse_prefix(s: &str) -> Option<(Self, &str)> {
+        let mut shift = 0i8;
+        let mut chars = s.char_indices();
+
+        for (index, c) in chars.by_ref() {
+            match c {
+                'b' | '♭' => shift -= 1,
+                '#' | '♯' => shift += 1,
+                '1'..='7' => {
+                    let degree = Self {
+                        degree: c as u8 - b'0',
+                        shift
+                    };
+                    return (shift.abs() <= 1).then_some((degree, &s[index + 1..]));
+                }
+                _ => return None
+            }
+        }
+
+        None
+    }
+
+    /// Degree of a note in a key, spelled by letter distance from the tonic
+    ///
+    /// The note is first respelled for the key (see [`Key::respell`]), so
+    /// `A#` in Bb is `1`, not `#7`.
+    pub fn of(note: NoteName, key: &Key) -> Self {
+        let note = key.respell(note);
+        let steps = (note.letter.index() + 7 - key.tonic.letter.index()) % 7;
+        let natural = Interval::natural(steps + 1);
+        let semitones = note.note().to_semitone() as i8 - key.tonic.note().to_semitone() as i8;
+        let mut shift = (semitones - natural.semitones as i8).rem_euclid(12);
+        if shift > 6 {
+            shift -= 12;
+        }
+
+        Self {
+            degree: steps + 1,
+            shift
+        }
+    }
+
+    /// Note of the degree in a key
+    pub fn note_in(&self, key: &Key) -> NoteName {
+        let natural = Interval::natural(self.degree);
+        let note = key
+            .tonic
+            .note()
+            .transpose(natural.semitones as i32 + self.shift as i32);
+        let spelled = NoteName::spelled_as(note, key.tonic.letter.step(self.degree - 1));
+
+        if spelled.is_practical() {
+            spelled
+        } else {
+            key.spell(note)
+        }
+    }
+}
+
+impl fmt::Display for ScaleDegree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let accidental = if self.shift > 0 { "#" } else { "b" };
+        for _ in 0..self.shift.unsigned_abs() {
+            f.write_str(accidental)?;
+        }
+        write!(f, "{}", self.degree)
+    }
+}
+
+/// Chord in Nashville notation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct NashvilleChord {
+    pub degree:    ScaleDegree,
+    /// Quality as written (`m`, `7`, `sus4`)
+    pub quality:   String,
+    pub bass:      Option<ScaleDegree>,
+    /// Parsed quality, `None` when the spelling is not recognized
+    pub structure: Option<ChordQuality>
+}
+
+impl NashvilleChord {
+    /// Parse chord like `1`, `4/6`, `6m7`, `b7`
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        let (main, bass) = match s.rsplit_once('/') {
+            Some((main, bass)) if ScaleDegree::parse(bass).is_some() => {
+                (main, ScaleDegree::parse(bass))
+            }
+            _ => (s, None)
+        };
+
+        let (degree, quality) = ScaleDegree::parse_prefix(main)?;
+
+        Some(Self {
+            degree,
+            quality: quality.to_string(),
+            bass,
+            structure: ChordQuality::parse(quality)
+        })
+    }
+
+    /// Convert a chord to numbers relative to the key
+    pub fn from_chord(chord: &Chord, key: &Key) -> Option<Self> {
+        let root = chord.root_note()?;
+
+        Some(Self {
+            degree:    ScaleDegree::of(root, key),
+            quality:   chord.quality.clone(),
+            bass:      chord.bass_note().map(|bass| ScaleDegree::of(bass, key)),
+            structure: chord.structure.clone()
+        })
+    }
+
+    /// Concrete chord in the given key
+    pub fn to_chord(&self, key: &Key) -> Chord {
+        Chord {
+            root:      self.degree.note_in(key).to_string(),
+            quality:   self.quality.clone(),
+            bass:      self.bass.map(|bass| bass.note_in(key).to_string()),
+            structure: self.structure.clone()
+        }
+    }
+}
+
+impl fmt::Display for NashvilleChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.degree, self.quality)?;
+        if let Some(bass) = &self.bass {
+            write!(f, "/{}", bass)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace chords in ChordPro content with Nashville numbers
+///
+/// Tokens that are not chords (annotations, `N.C.`) are left as they are.
+pub fn to_nashville_content(content: &str, key: &Key) -> String {
+    let mut tree = SyntaxTree::parse(content);
+    for token in tree.chords_mut() {
+        if let Some(number) = Chord::parse(token).and_then(|c| NashvilleChord::from_chord(&c, key))
+        {
+            *token = number.to_string();
+        }
+    }
+    tree.to_string()
+}
+
+/// Replace Nashville numbers in ChordPro content with chords in `key`
+///
+/// The `{key}` directive is set (or added) to the rendered key.
+pub fn from_nashville_content(content: &str, key: &Key) -> String {
+    let mut tree = SyntaxTree::parse(content);
+    for token in tree.chords_mut() {
+        if let Some(number) = NashvilleChord::parse(token) {
+            *token = number.to_chord(key).to_string();
+        }
+    }
+    tree.set_directive("key", &key.to_string());
+    tree.to_string()
+}
+
+/// Write a song as a Nashville chart relative to its key
+///
+/// The key comes from `{key}` or is detected from chords; `None` when the
+/// song has no key and no chords.
+pub fn song_to_nashville(song: &ParsedSong) -> Option<String> {
+    let key = song.estimated_key()?;
+    let content = ChordProWriter::default().write(song);
+    Some(to_nashville_content(&content, &key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChordProParser;
+
+    fn key(s: &str) -> Key {
+        Key::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_scale_degree() {
+        assert_eq!(ScaleDegree::parse("5").unwrap().degree, 5);
+        assert_eq!(ScaleDegree::parse("b7").unwrap().shift, -1);
+        assert_eq!(ScaleDegree::parse("#4").unwrap().shift, 1);
+        assert!(ScaleDegree::parse("8").is_none());
+        assert!(ScaleDegree::parse("bb3").is_none());
+        assert!(ScaleDegree::parse("5m").is_none());
+        assert!(ScaleDegree::parse("").is_none());
+    }
+
+    #[test]
+    fn test_parse_nashville_chord() {
+        let chord = NashvilleChord::parse("6m7").unwrap();
+        assert_eq!(chord.degree.degree, 6);
+        assert_eq!(chord.quality, "m7");
+        assert!(chord.structure.unwrap().is_minor());
+
+        let chord = NashvilleChord::parse("4/6").unwrap();
+        assert_eq!(chord.bass.unwrap().degree, 6);
+
+        let chord = NashvilleChord::parse("b7sus4").unwrap();
+        assert_eq!(chord.to_string(), "b7sus4");
+
+        assert!(NashvilleChord::parse("C").is_none());
+        assert!(NashvilleChord::parse("*riff").is_none());
+    }
+
+    #[test]
+    fn test_from_chord() {
+        let g = key("G");
+        let number = |s: &str| {
+            NashvilleChord::from_chord(&Chord::parse(s).unwrap(), &g)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(number("G"), "1");
+        assert_eq!(number("C"), "4");
+        assert_eq!(number("D/F#"), "5/7");
+        assert_eq!(number("Em7"), "6m7");
+        assert_eq!(number("F"), "b7");
+        assert_eq!(number("C#dim"), "#4dim");
+        assert_eq!(number("Bb"), "b3");
+    }
+
+    #[test]
+    fn test_from_chord_in_minor_key() {
+        let chord = Chord::parse("C").unwrap();
+        let number = NashvilleChord::from_chord(&chord, &key("Am")).unwrap();
+        assert_eq!(number.to_string(), "b3");
+    }
+
+    #[test]
+    fn test_to_chord() {
+        let chord = NashvilleChord::parse("4").unwrap();
+        assert_eq!(chord.to_chord(&key("F")).to_string(), "Bb");
+
+        let chord = NashvilleChord::parse("5/7").unwrap();
+        assert_eq!(chord.to_chord(&key("E")).to_string(), "B/D#");
+
+        let chord = NashvilleChord::parse("b7").unwrap();
+        assert_eq!(chord.to_chord(&key("D")).to_string(), "C");
+
+        let chord = NashvilleChord::parse("6m7").unwrap();
+        assert_eq!(chord.to_chord(&key("Db")).to_string(), "Bbm7");
+    }
+
+    #[test]
+    fn test_content_round_trip() {
+        let content = "{title: Song}\n{key: G}\n[G]Hello [C/G]world [D7]again [*riff]\n";
+        let numbers = to_nashville_content(content, &key("G"));
+        assert_eq!(
+            numbers,
+            "{title: Song}\n{key: G}\n[1]Hello [4/1]world [57]again [*riff]\n"
+        );
+
+        let rendered = from_nashville_content(&numbers, &key("A"));
+        assert_eq!(
+            rendered,
+            "{title: Song}\n{key: A}\n[A]Hello [D/A]world [E7]again [*riff]\n"
+        );
+        assert_eq!(from_nashville_content(&numbers, &key("G")), content);
+    }
+
+    #[test]
+    fn test_song_to_nashville() {
+        let song = ChordProParser::parse("[C]Hello [Am]world [F]and [G]more [C]");
+        let chart = song_to_nashville(&song).unwrap();
+        assert!(chart.contains("[1]Hello [6m]world [4]and [5]more [1]"));
+
+        assert!(song_to_nashville(&ChordProParser::parse("Hello")).is_none());
+
+        let song = ChordProParser::parse("[A#]Hello [D#]world [F]again [A#/D]end");
+        let chart = song_to_nashville(&song).unwrap();
+        assert!(chart.contains("[1]Hello [4]world [5]again [1/3]end"));
+    }
+}
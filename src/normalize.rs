@@ -0,0 +1,364 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Canonical chord spelling
+//!
+//! Rewrites chords that mean the same thing (`A#`, `Bb`, `Hb`; `maj7`, `M7`,
+//! `Δ`) into one spelling chosen by [`ChordStyle`].
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Accidental, Alteration, Chord, ChordProParser, ChordQuality, Interval, Key, Note, Seventh,
+    Suspension, SyntaxTree, Triad
+};
+
+/// Accidentals of root and bass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Spelling {
+    /// Keep the accidental as written (`Hb` becomes `Bb`); in content, spell
+    /// in the song key when it is known
+    #[default]
+    Auto,
+    Sharps,
+    Flats,
+    /// Scale notes as in the given key; other notes keep the written
+    /// accidental (`E#` becomes `F`)
+    Key(Key)
+}
+
+/// Major seventh symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MajorSeventhStyle {
+    /// `Cmaj7`, `Cmaj9`
+    #[default]
+    Maj,
+    /// `CΔ7`, `CΔ9`
+    Delta
+}
+
+/// Minor triad symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MinorStyle {
+    /// `Am7`
+    #[default]
+    M,
+    /// `Amin7`
+    Min,
+    /// `A-7`
+    Dash
+}
+
+/// Canonical chord spelling options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordStyle {
+    pub spelling:      Spelling,
+    pub major_seventh: MajorSeventhStyle,
+    pub minor:         MinorStyle
+}
+
+impl Chord {
+    /// Chord spelled in the canonical form of the style
+    ///
+    /// Qualities that cannot be rewritten without changing their meaning are
+    /// kept as written.
+    pub fn normalize(&self, style: &ChordStyle) -> Chord {
+        let quality = self
+            .structure
+            .as_ref()
+            .and_then(|structure| format_quality(structure, style))
+            .unwrap_or_else(|| self.quality.clone());
+
+        Chord {
+            root: spell(&self.root, style.spelling),
+            structure: ChordQuality::parse(&quality),
+            quality,
+            bass: self.bass.as_deref().map(|bass| spell(bass, style.spelling))
+        }
+    }
+}
+
+/// Normalize every chord in ChordPro content, keeping everything else as
+/// written
+///
+/// With [`Spelling::Auto`] chords are spelled in the key from `{key}` or the
+/// detected key.
+pub fn normalize_content(content: &str, style: &ChordStyle) -> String {
+    let style = match style.spelling {
+        Spelling::Auto => ChordStyle {
+            spelling: ChordProParser::parse(content)
+                .estimated_key()
+                .map_or(Spelling::Auto, Spelling::Key),
+            ..*style
+        },
+        _ => *style
+    };
+
+    let mut tree = SyntaxTree::parse(content);
+    for token in tree.chords_mut() {
+        if let Some(chord) = Chord::parse(token) {
+            *token = chord.normalize(&style).to_string();
+        }
+    }
+    tree.to_string()
+}
+
+fn spell(note: &str, spelling: Spelling) -> String {
+    let Some((parsed, flat)) = Note::parse(note) else {
+        return note.to_string();
+    };
+
+    match spelling {
+        Spelling::Auto if flat => parsed.to_flat_string().to_string(),
+        Spelling::Auto | Spelling::Sharps => parsed.to_sharp_string().to_string(),
+        Spelling::Flats => parsed.to_flat_string().to_string(),
+        Spelling::Key(key) => match key.scale().into_iter().find(|n| n.note() == parsed) {
+            Some(name) => name.to_string(),
+            None => spell(note, Spelling::Auto)
+        }
+    }
+}
+
+/// Canonical quality text, `None` when the result would not parse back to
+/// the same intervals
+fn format_quality(quality: &ChordQuality, style: &ChordStyle) -> Option<String> {
+    let triad = quality.sounding_triad();
+    let implied = match (quality.triad, triad) {
+        (Triad::Minor, Triad::Diminished) => Some(altered(5, Accidental::Flat)),
+        (Triad::Major, Triad::Augmented) => Some(altered(5, Accidental::Sharp)),
+        _ => None
+    };
+    let mut alterations: Vec<Alteration> = quality
+        .alterations
+        .iter()
+        .copied()
+        .filter(|a| Some(*a) != implied)
+        .collect();
+
+    let minor = match style.minor {
+        MinorStyle::M => "m",
+        MinorStyle::Min => "min",
+        MinorStyle::Dash => "-"
+    };
+    let number = quality.extension.unwrap_or(7);
+    let major = |prefix: &str| match style.major_seventh {
+        MajorSeventhStyle::Maj if prefix.is_empty() => format!("maj{number}"),
+        MajorSeventhStyle::Maj => format!("{prefix}Maj{number}"),
+        MajorSeventhStyle::Delta => format!("{prefix}Δ{number}")
+    };
+
+    let mut out = match (triad, quality.seventh) {
+        (Triad::Major, _) if quality.is_power_chord() => "5".to_string(),
+        (Triad::Major, None) => String::new(),
+        (Triad::Major, Some(Seventh::Minor)) => number.to_string(),
+        (Triad::Major, Some(Seventh::Major)) => major(""),
+        (Triad::Minor, None) => minor.to_string(),
+        (Triad::Minor, Some(Seventh::Minor)) => format!("{minor}{number}"),
+        (Triad::Minor, Some(Seventh::Major)) => major(minor),
+        (Triad::Diminished, None) => "dim".to_string(),
+        (Triad::Diminished, Some(Seventh::Diminished)) => format!("dim{number}"),
+        (Triad::Diminished, Some(Seventh::Minor)) => format!("{minor}{number}b5"),
+        (Triad::Augmented, None) => "aug".to_string(),
+        (Triad::Augmented, Some(seventh @ (Seventh::Minor | Seventh::Major))) => {
+            alterations.insert(0, altered(5, Accidental::Sharp));
+            match seventh {
+                Seventh::Major => major(""),
+                _ => number.to_string()
+            }
+        }
+        _ => return None
+    };
+
+    let mut additions = quality.additions.clone();
+    if additions.contains(&6) {
+        out.push('6');
+        if additions.contains(&9) {
+            out.push_str("/9");
+            additions.retain(|a| *a != 9);
+        }
+        additions.retain(|a| *a != 6);
+    }
+
+    match quality.suspension {
+        Some(Suspension::Sus2) => out.push_str("sus2"),
+        Some(Suspension::Sus4) => out.push_str("sus4"),
+        None => {}
+    }
+
+    for addition in additions {
+        out.push_str(&format!("add{}", addition));
+    }
+
+    let alterations: Vec<String> = alterations
+        .iter()
+        .map(|a| {
+            Interval::natural(a.degree)
+                .altered(shift(a.accidental))
+                .to_string()
+        })
+        .collect();
+    match alterations.as_slice() {
+        [] => {}
+        [single] if !out.is_empty() => out.push_str(single),
+        _ => out.push_str(&format!("({})", alterations.join(",")))
+    }
+
+    if !quality.is_power_chord() {
+        for omission in &quality.omissions {
+            out.push_str(&format!("no{}", omission));
+        }
+    }
+
+    let sorted = |quality: &ChordQuality| {
+        let mut intervals = quality.intervals();
+        intervals.sort();
+        intervals.dedup();
+        intervals
+    };
+    ChordQuality::parse(&out)
+        .filter(|parsed| sorted(parsed) == sorted(quality))
+        .map(|_| out)
+}
+
+fn altered(degree: u8, accidental: Accidental) -> Alteration {
+    Alteration {
+        degree,
+        accidental
+    }
+}
+
+fn shift(accidental: Accidental) -> i8 {
+    match accidental {
+        Accidental::Flat => -1,
+        Accidental::Sharp => 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::ChordMeaning;
+
+    fn normalize(s: &str, style: &ChordStyle) -> String {
+        Chord::parse(s).unwrap().normalize(style).to_string()
+    }
+
+    #[test]
+    fn test_chord_meaning() {
+        let chord = |s: &str| Chord::parse(s).unwrap().meaning();
+        assert_eq!(chord("A#"), chord("Bb"));
+        assert_eq!(chord("Hb"), chord("Bb"));
+        assert_eq!(chord("Bbmaj7"), chord("BbM7"));
+        assert_eq!(chord("Bbmaj7"), chord("BbΔ7"));
+        assert_eq!(chord("BbΔ"), chord("A#maj7"));
+        assert_eq!(chord("Bø"), chord("Bm7b5"));
+        assert_eq!(chord("Am7/G"), chord("Amin7/G"));
+        assert_ne!(chord("Bb"), chord("Bbm"));
+        assert_ne!(chord("C/E"), chord("C"));
+        assert_ne!(chord("Cxyz"), chord("Cabc"));
+        assert_eq!(chord("Cxyz"), chord("B#xyz"));
+
+        let distinct: HashSet<ChordMeaning> = ["A#", "Bb", "Hb", "Bbmaj7", "BbM7", "BbΔ7", "Bbm"]
+            .into_iter()
+            .map(chord)
+            .collect();
+        assert_eq!(distinct.len(), 3);
+    }
+
+    #[test]
+    fn test_normalize_default_style() {
+        let style = ChordStyle::default();
+        assert_eq!(normalize("Hb", &style), "Bb");
+        assert_eq!(normalize("A#", &style), "A#");
+        assert_eq!(normalize("BbM7", &style), "Bbmaj7");
+        assert_eq!(normalize("BbΔ", &style), "Bbmaj7");
+        assert_eq!(normalize("Amin7", &style), "Am7");
+        assert_eq!(normalize("Bø", &style), "Bm7b5");
+        assert_eq!(normalize("Cmi(maj7)", &style), "CmMaj7");
+        assert_eq!(normalize("C+", &style), "Caug");
+        assert_eq!(normalize("C7(#5)", &style), "C7#5");
+        assert_eq!(normalize("G7(b9,#11)", &style), "G7(b9,#11)");
+        assert_eq!(normalize("Csus", &style), "Csus4");
+        assert_eq!(normalize("C69", &style), "C6/9");
+        assert_eq!(normalize("Cm(add9)", &style), "Cmadd9");
+        assert_eq!(normalize("E5", &style), "E5");
+        assert_eq!(normalize("Cxyz", &style), "Cxyz");
+    }
+
+    #[test]
+    fn test_normalize_custom_style() {
+        let style = ChordStyle {
+            spelling:      Spelling::Flats,
+            major_seventh: MajorSeventhStyle::Delta,
+            minor:         MinorStyle::Min
+        };
+        assert_eq!(normalize("A#maj7", &style), "BbΔ7");
+        assert_eq!(normalize("C#m7/G#", &style), "Dbmin7/Ab");
+        assert_eq!(normalize("Cm(maj7)", &style), "CminΔ7");
+        assert_eq!(normalize("Fmaj9", &style), "FΔ9");
+
+        let style = ChordStyle {
+            spelling: Spelling::Key(Key::parse("E").unwrap()),
+            minor: MinorStyle::Dash,
+            ..Default::default()
+        };
+        assert_eq!(normalize("Abm7", &style), "G#-7");
+        assert_eq!(normalize("Gb/Bb", &style), "F#/Bb");
+        assert_eq!(normalize("E#", &style), "F");
+    }
+
+    #[test]
+    fn test_normalize_preserves_meaning() {
+        let styles = [
+            ChordStyle::default(),
+            ChordStyle {
+                spelling:      Spelling::Sharps,
+                major_seventh: MajorSeventhStyle::Delta,
+                minor:         MinorStyle::Dash
+            }
+        ];
+        for s in [
+            "C", "Cm", "C7", "Cmaj7", "CmM7", "Cdim", "Cdim7", "Cø", "Caug", "C7#5", "Cmaj7#5",
+            "C9", "Cm11", "C13", "C7b9", "C7alt", "Csus2", "C7sus4", "Cadd9", "C6", "Cm6", "C6/9",
+            "C5", "C7no3", "Cmaj9#11", "Cm(b5)"
+        ] {
+            let chord = Chord::parse(s).unwrap();
+            for style in &styles {
+                let normalized = chord.normalize(style);
+                assert!(normalized.same_meaning(&chord), "{} → {}", s, normalized);
+                let parsed = Chord::parse(&normalized.to_string()).unwrap();
+                assert!(parsed.same_meaning(&chord));
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_content() {
+        let content = "{key: F}\n[A#maj7]Hello [Dmin]world [CΔ/E]again [*riff]\n";
+        assert_eq!(
+            normalize_content(content, &ChordStyle::default()),
+            "{key: F}\n[Bbmaj7]Hello [Dm]world [Cmaj7/E]again [*riff]\n"
+        );
+
+        let content = "[E]Hello [Bb]world [A]";
+        assert_eq!(
+            normalize_content(content, &ChordStyle::default()),
+            "[E]Hello [Bb]world [A]"
+        );
+
+        let content = "{key: G}\n[G]x [Bb]y [Hb]z [Gb]w";
+        assert_eq!(
+            normalize_content(content, &ChordStyle::default()),
+            "{key: G}\n[G]x [Bb]y [Bb]z [F#]w"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: 2025 Revelation Team
 // SPDX-License-Identifier: MIT
 
+use super::{NoteName, NoteNaming, naming::latin_lookalike};
+
 /// Musical note (for transposition)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Note {
@@ -27,7 +29,7 @@ impl Note {
         }
 
         let chars: Vec<char> = s.chars().collect();
-        let base = chars[0].to_ascii_uppercase();
+        let base = latin_lookalike(chars[0]).to_ascii_uppercase();
         let modifier = chars.get(1).copied();
 
         let (note, is_flat) = match (base, modifier) {
@@ -52,6 +54,7 @@ impl Note {
             ('B', Some('#')) => (Note::C, false),
             ('B', Some('b')) => (Note::ASharp, true),
             ('B', _) => (Note::B, false),
+            ('H', Some('b')) => (Note::ASharp, true),
             ('H', _) => (Note::B, false),
             _ => return None
         };
@@ -59,6 +62,22 @@ impl Note {
         Some((note, is_flat))
     }
 
+    /// Parse a whole note name in the given naming system
+    pub fn parse_with(s: &str, naming: NoteNaming) -> Option<(Self, bool)> {
+        let name = naming.parse_note(s)?;
+        Some((name.note(), name.shift < 0))
+    }
+
+    /// Note name in the given naming system
+    pub fn name(self, naming: NoteNaming, use_flats: bool) -> String {
+        let spelled = if use_flats {
+            self.to_flat_string()
+        } else {
+            self.to_sharp_string()
+        };
+        naming.format(NoteName::parse(spelled).expect("note names are valid"))
+    }
+
     /// Convert to semitone index (0-11)
     pub fn to_semitone(self) -> u8 {
         match self {
@@ -198,6 +217,44 @@ mod tests {
         assert!(is_flat);
     }
 
+    #[test]
+    fn test_parse_german_h() {
+        let (note, is_flat) = Note::parse("Hb").unwrap();
+        assert_eq!(note, Note::ASharp);
+        assert!(is_flat);
+    }
+
+    #[test]
+    fn test_parse_cyrillic_lookalikes() {
+        assert_eq!(Note::parse("С").unwrap().0, Note::C);
+        assert_eq!(Note::parse("А#").unwrap().0, Note::ASharp);
+        assert_eq!(Note::parse("Еb").unwrap().0, Note::DSharp);
+        assert_eq!(Note::parse("Н").unwrap().0, Note::B);
+        assert_eq!(Note::parse("В").unwrap().0, Note::B);
+    }
+
+    #[test]
+    fn test_parse_with_naming() {
+        assert_eq!(
+            Note::parse_with("B", NoteNaming::German),
+            Some((Note::ASharp, true))
+        );
+        assert_eq!(
+            Note::parse_with("Ми", NoteNaming::SolfegeCyrillic),
+            Some((Note::E, false))
+        );
+        assert!(Note::parse_with("Mi", NoteNaming::English).is_none());
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(Note::B.name(NoteNaming::German, false), "H");
+        assert_eq!(Note::ASharp.name(NoteNaming::German, true), "B");
+        assert_eq!(Note::ASharp.name(NoteNaming::German, false), "A#");
+        assert_eq!(Note::FSharp.name(NoteNaming::Solfege, false), "Fa#");
+        assert_eq!(Note::C.name(NoteNaming::SolfegeCyrillic, false), "До");
+    }
+
     #[test]
     fn test_parse_lowercase() {
         assert_eq!(Note::parse("c").unwrap().0, Note::C);
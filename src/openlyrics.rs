@@ -0,0 +1,797 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! OpenLyrics 0.9 import and export
+//!
+//! OpenLyrics is the XML song format of OpenLP and other worship software.
+//! Verses are stored once under names like `v1` or `c` and arranged by
+//! `<verseOrder>`; repeats of the last chorus become `{chorus}` recalls.
+//! Verses with another `lang` than the first one become translations.
+//! Elements are matched by local name, so documents without the namespace
+//! are read too.
+
+use roxmltree::{Document, Node};
+
+use super::{
+    Chord, CreateSong, ImportError, InterchangeSong, ParsedSong, PositionedChord, SongLine,
+    SongSection, SongSectionType, SongTranslation,
+    interchange::{NamedSection, arrange, escape_xml, lyrics_song, theme_categories}
+};
+
+const NAMESPACE: &str = "http://openlyrics.info/namespace/2009/song";
+
+/// OpenLyrics XML converter
+pub struct OpenLyrics;
+
+impl OpenLyrics {
+    /// Read song from OpenLyrics XML
+    pub fn import(xml: &str) -> Result<InterchangeSong, ImportError> {
+        let document = Document::parse(xml).map_err(|e| ImportError::Xml(e.to_string()))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "song" {
+            return Err(ImportError::UnexpectedRoot(
+                root.tag_name().name().to_string()
+            ));
+        }
+
+        let properties = child(root, "properties");
+        let lyrics = child(root, "lyrics").ok_or(ImportError::MissingElement("lyrics"))?;
+        let verses: Vec<Verse> = children(lyrics, "verse").map(Verse::read).collect();
+
+        let language = verses.iter().find_map(|verse| verse.lang.clone());
+        let mut languages: Vec<&str> = Vec::new();
+        for lang in verses
+            .iter()
+            .filter_map(|verse| verse.translation(&language))
+        {
+            if !languages.contains(&lang) {
+                languages.push(lang);
+            }
+        }
+
+        let property = |name| properties.and_then(|p| child(p, name)).and_then(text);
+        let titles: Vec<(Option<&str>, String)> = properties
+            .and_then(|p| child(p, "titles"))
+            .into_iter()
+            .flat_map(|titles| children(titles, "title"))
+            .filter_map(|title| Some((title.attribute("lang"), text(title)?)))
+            .collect();
+        let mut main_titles = titles
+            .iter()
+            .filter(|(lang, _)| lang.is_none() || *lang == language.as_deref())
+            .map(|(_, title)| title.clone());
+        let title = main_titles
+            .next()
+            .or_else(|| titles.first().map(|(_, title)| title.clone()))
+            .ok_or(ImportError::MissingElement("title"))?;
+        let title_alt = main_titles.next();
+
+        let authors_of = |kinds: &[Option<&str>]| {
+            properties
+                .and_then(|p| child(p, "authors"))
+                .into_iter()
+                .flat_map(|authors| children(authors, "author"))
+                .filter(|author| kinds.contains(&author.attribute("type")))
+                .collect::<Vec<_>>()
+        };
+        let authors = |kinds: &[Option<&str>]| {
+            let names: Vec<String> = authors_of(kinds).into_iter().filter_map(text).collect();
+            (!names.is_empty()).then(|| names.join(", "))
+        };
+        let translator_language = authors_of(&[Some("translation")])
+            .into_iter()
+            .find_map(|author| author.attribute("lang"))
+            .map(str::to_string);
+
+        let order: Vec<String> = property("verseOrder")
+            .map(|order| order.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        let themes: Vec<String> = properties
+            .and_then(|p| child(p, "themes"))
+            .into_iter()
+            .flat_map(|themes| children(themes, "theme"))
+            .filter_map(text)
+            .collect();
+        let tempo = properties
+            .and_then(|p| child(p, "tempo"))
+            .filter(|tempo| tempo.attribute("type").is_none_or(|t| t == "bpm"))
+            .and_then(text)
+            .and_then(|tempo| tempo.parse().ok());
+
+        let sections_in = |lang: Option<&str>| {
+            let defined: Vec<NamedSection> = verses
+                .iter()
+                .filter(|verse| verse.translation(&language) == lang)
+                .map(Verse::section)
+                .collect();
+            arrange(&defined, &order)
+        };
+
+        let parsed = ParsedSong {
+            key: property("key"),
+            tempo,
+            time_signature: property("timeSignature"),
+            ..lyrics_song(Some(title.clone()), sections_in(None))
+        };
+
+        let translations = languages
+            .into_iter()
+            .map(|lang| {
+                let title = titles
+                    .iter()
+                    .find(|(title_lang, _)| *title_lang == Some(lang))
+                    .map(|(_, title)| title.clone());
+                SongTranslation {
+                    language: lang.to_string(),
+                    parsed: lyrics_song(title.clone(), sections_in(Some(lang))),
+                    title
+                }
+            })
+            .collect();
+
+        let song = CreateSong {
+            songbook_id: None,
+            number: None,
+            title,
+            title_alt,
+            author_lyrics: authors(&[Some("words"), None]),
+            author_music: authors(&[Some("music")]),
+            translator: authors(&[Some("translation")]),
+            year_written: property("released")
+                .and_then(|released| released.get(..4)?.parse().ok()),
+            copyright: property("copyright"),
+            original_key: parsed.key.clone(),
+            tempo: parsed.tempo,
+            time_signature: parsed.time_signature.clone(),
+            content: String::new(),
+            categories: theme_categories(&themes),
+            tag_ids: Vec::new(),
+            source_url: None
+        };
+
+        Ok(InterchangeSong {
+            language,
+            translator_language,
+            ccli_number: property("ccliNo"),
+            themes,
+            translations,
+            ..InterchangeSong::new(song, parsed)
+        })
+    }
+
+    /// Write song as OpenLyrics XML
+    pub fn export(song: &InterchangeSong) -> String {
+        let meta = &song.song;
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<song xmlns=\"{}\" version=\"0.9\" createdIn=\"Revelation\" modifiedIn=\"Revelation\">\n",
+            NAMESPACE
+        ));
+        out.push_str("  <properties>\n");
+
+        out.push_str("    <titles>\n");
+        out.push_str(&format!(
+            "      <title{}>{}</title>\n",
+            lang_attribute(song.language.as_deref()),
+            escape_xml(&meta.title)
+        ));
+        if let Some(ref title_alt) = meta.title_alt {
+            out.push_str(&format!("      <title>{}</title>\n", escape_xml(title_alt)));
+        }
+        for translation in &song.translations {
+            if let Some(ref title) = translation.title {
+                out.push_str(&format!(
+                    "      <title lang=\"{}\">{}</title>\n",
+                    escape_xml(&translation.language),
+                    escape_xml(title)
+                ));
+            }
+        }
+        out.push_str("    </titles>\n");
+
+        let authors = [
+            ("words", &meta.author_lyrics),
+            ("music", &meta.author_music),
+            ("translation", &meta.translator)
+        ];
+        if authors.iter().any(|(_, name)| name.is_some()) {
+            out.push_str("    <authors>\n");
+            for (kind, name) in authors {
+                let Some(name) = name else {
+                    continue;
+                };
+                let lang = if kind == "translation" {
+                    lang_attribute(song.translator_language.as_deref())
+                } else {
+                    String::new()
+                };
+                out.push_str(&format!(
+                    "      <author type=\"{}\"{}>{}</author>\n",
+                    kind,
+                    lang,
+                    escape_xml(name)
+                ));
+            }
+            out.push_str("    </authors>\n");
+        }
+
+        let key = meta
+            .original_key
+            .clone()
+            .or_else(|| song.parsed.key.clone());
+        let fields = [
+            ("copyright", meta.copyright.clone()),
+            ("ccliNo", song.ccli_number.clone()),
+            ("released", meta.year_written.map(|year| year.to_string())),
+            ("key", key),
+            ("timeSignature", meta.time_signature.clone())
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                out.push_str(&format!("    <{0}>{1}</{0}>\n", name, escape_xml(&value)));
+            }
+        }
+        if let Some(tempo) = meta.tempo.or(song.parsed.tempo) {
+            out.push_str(&format!("    <tempo type=\"bpm\">{}</tempo>\n", tempo));
+        }
+
+        let names = verse_names(&song.parsed.sections);
+        let order: Vec<&str> = names.iter().flatten().map(String::as_str).collect();
+        if !order.is_empty() {
+            out.push_str(&format!(
+                "    <verseOrder>{}</verseOrder>\n",
+                order.join(" ")
+            ));
+        }
+
+        let themes: Vec<String> = if song.themes.is_empty() {
+            meta.categories
+                .iter()
+                .map(|c| c.name_en().to_string())
+                .collect()
+        } else {
+            song.themes.clone()
+        };
+        if !themes.is_empty() {
+            out.push_str("    <themes>\n");
+            for theme in themes {
+                out.push_str(&format!("      <theme>{}</theme>\n", escape_xml(&theme)));
+            }
+            out.push_str("    </themes>\n");
+        }
+        out.push_str("  </properties>\n");
+
+        out.push_str("  <lyrics>\n");
+        write_verses(&song.parsed.sections, song.language.as_deref(), &mut out);
+        for translation in &song.translations {
+            write_verses(
+                &translation.parsed.sections,
+                Some(&translation.language),
+                &mut out
+            );
+        }
+        out.push_str("  </lyrics>\n");
+        out.push_str("</song>\n");
+        out
+    }
+}
+
+/// `<verse>` element read into lines
+struct Verse {
+    name:  String,
+    lang:  Option<String>,
+    lines: Vec<SongLine>
+}
+
+impl Verse {
+    /// Language of the verse when it differs from the main `language`
+    fn translation(&self, language: &Option<String>) -> Option<&str> {
+        self.lang.as_deref().filter(|_| self.lang != *language)
+    }
+
+    fn section(&self) -> NamedSection {
+        let (section_type, label) = section_kind(&self.name);
+        NamedSection {
+            name:    self.name.clone(),
+            section: SongSection {
+                section_type,
+                label,
+                lines: self.lines.clone(),
+                recall: None,
+                grid: None
+            }
+        }
+    }
+
+    fn read(node: Node) -> Self {
+        let mut builder = LineBuilder::default();
+        for lines in node.children().filter(Node::is_element) {
+            builder.walk(lines);
+            builder.finish();
+        }
+
+        Self {
+            name:  node.attribute("name").unwrap_or("v").to_string(),
+            lang:  node.attribute("lang").map(str::to_string),
+            lines: builder.lines
+        }
+    }
+}
+
+/// Collects mixed `<lines>` content into song lines
+#[derive(Default)]
+struct LineBuilder {
+    lines:  Vec<SongLine>,
+    text:   String,
+    chords: Vec<PositionedChord>
+}
+
+impl LineBuilder {
+    fn walk(&mut self, node: Node) {
+        for node in node.children() {
+            if let Some(text) = node.text().filter(|_| node.is_text()) {
+                self.push_text(text);
+                continue;
+            }
+            match node.tag_name().name() {
+                "br" => self.break_line(),
+                "comment" => {}
+                "chord" => {
+                    if let Some(chord) = chord(node) {
+                        self.chords.push(PositionedChord {
+                            position: self.text.chars().count(),
+                            chord
+                        });
+                    }
+                    self.walk(node);
+                }
+                "line" => {
+                    self.walk(node);
+                    self.break_line();
+                }
+                _ => self.walk(node)
+            }
+        }
+    }
+
+    /// Append text; line breaks in the source are formatting and read as
+    /// spaces
+    fn push_text(&mut self, text: &str) {
+        let mut rest = text;
+        while let Some(start) = rest.find(char::is_whitespace) {
+            let len = rest[start..]
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len() - start);
+            let space = &rest[start..start + len];
+            self.text.push_str(&rest[..start]);
+            self.text
+                .push_str(if space.contains('\n') { " " } else { space });
+            rest = &rest[start + len..];
+        }
+        self.text.push_str(rest);
+    }
+
+    fn break_line(&mut self) {
+        let leading = self.text.chars().take_while(|c| c.is_whitespace()).count();
+        let text = self.text.trim().to_string();
+        let len = text.chars().count();
+        let chords = std::mem::take(&mut self.chords)
+            .into_iter()
+            .map(|chord| PositionedChord {
+                position: chord.position.saturating_sub(leading).min(len),
+                ..chord
+            })
+            .collect();
+
+        self.lines.push(SongLine {
+            text,
+            chords
+        });
+        self.text.clear();
+    }
+
+    /// End of a `<lines>` element
+    fn finish(&mut self) {
+        if !self.text.trim().is_empty() || !self.chords.is_empty() {
+            self.break_line();
+        }
+        self.text.clear();
+    }
+}
+
+/// Chord of a `<chord>` element, from `name` or `root`, `structure` and
+/// `bass`
+fn chord(node: Node) -> Option<Chord> {
+    if let Some(name) = node.attribute("name") {
+        return Chord::parse(name);
+    }
+
+    let structure = match node.attribute("structure").unwrap_or("") {
+        "major" => "",
+        "minor" => "m",
+        "dom7" => "7",
+        structure => structure
+    };
+    let mut name = format!("{}{}", node.attribute("root")?, structure);
+    if let Some(bass) = node.attribute("bass") {
+        name.push_str(&format!("/{}", bass));
+    }
+    Chord::parse(&name)
+}
+
+/// Section type and label of a verse name like `v1`, `c` or `b2a`
+fn section_kind(name: &str) -> (SongSectionType, Option<String>) {
+    let mut chars = name.chars();
+    let section_type = match chars.next().map(|c| c.to_ascii_lowercase()) {
+        Some('v') => SongSectionType::Verse,
+        Some('c') => SongSectionType::Chorus,
+        Some('b') => SongSectionType::Bridge,
+        Some('p') => SongSectionType::PreChorus,
+        Some('i') => SongSectionType::Intro,
+        Some('e') => SongSectionType::Ending,
+        _ => SongSectionType::Other
+    };
+    let label = chars.as_str();
+    (section_type, (!label.is_empty()).then(|| label.to_string()))
+}
+
+/// OpenLyrics verse name letter
+fn letter(section_type: SongSectionType) -> char {
+    match section_type {
+        SongSectionType::Verse => 'v',
+        SongSectionType::Chorus => 'c',
+        SongSectionType::Bridge => 'b',
+        SongSectionType::PreChorus => 'p',
+        SongSectionType::Intro => 'i',
+        SongSectionType::Ending | SongSectionType::Outro => 'e',
+        _ => 'o'
+    }
+}
+
+/// Verse name of each section, recalls repeat the name of their chorus
+///
+/// Labels imported from names like `v2` give the name back, so a
+/// translation with only some verses keeps the names of the originals.
+/// Other sections are numbered per letter, skipping names already taken.
+fn verse_names(sections: &[SongSection]) -> Vec<Option<String>> {
+    let mut names: Vec<Option<String>> = vec![None; sections.len()];
+    let named =
+        |section: &SongSection| section.recall.is_none() && !section.section_type.is_verbatim();
+
+    for (index, section) in sections.iter().enumerate().filter(|(_, s)| named(s)) {
+        let name = section
+            .label
+            .as_deref()
+            .filter(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric()))
+            .map(|label| format!("{}{}", letter(section.section_type), label));
+        if name.is_some() && !names.contains(&name) {
+            names[index] = name;
+        }
+    }
+
+    for (index, section) in sections.iter().enumerate().filter(|(_, s)| named(s)) {
+        if names[index].is_some() {
+            continue;
+        }
+        let name = (1..)
+            .map(|count| Some(format!("{}{}", letter(section.section_type), count)))
+            .find(|name| !names.contains(name))
+            .flatten();
+        names[index] = name;
+    }
+
+    for (index, section) in sections.iter().enumerate() {
+        if let Some(recall) = section.recall {
+            names[index] = names.get(recall).cloned().flatten();
+        }
+    }
+    names
+}
+
+fn write_verses(sections: &[SongSection], lang: Option<&str>, out: &mut String) {
+    let names = verse_names(sections);
+    for (section, name) in sections.iter().zip(names) {
+        let Some(name) = name.filter(|_| section.recall.is_none()) else {
+            continue;
+        };
+        let lines: Vec<String> = section.lines.iter().map(write_line).collect();
+        out.push_str(&format!(
+            "    <verse name=\"{}\"{}>\n      <lines>{}</lines>\n    </verse>\n",
+            name,
+            lang_attribute(lang),
+            lines.join("<br/>")
+        ));
+    }
+}
+
+/// Line text with `<chord>` elements at chord positions
+fn write_line(line: &SongLine) -> String {
+    let mut sorted: Vec<&PositionedChord> = line.chords.iter().collect();
+    sorted.sort_by_key(|chord| chord.position);
+    let mut chords = sorted.into_iter().peekable();
+    let mut out = String::new();
+
+    for (position, c) in line.text.chars().enumerate() {
+        while let Some(chord) = chords.next_if(|chord| chord.position <= position) {
+            out.push_str(&chord_element(&chord.chord));
+        }
+        out.push_str(&escape_xml(&c.to_string()));
+    }
+    for chord in chords {
+        out.push_str(&chord_element(&chord.chord));
+    }
+    out
+}
+
+/// `<chord>` element with `root`, `structure` and `bass` as in 0.9
+fn chord_element(chord: &Chord) -> String {
+    let mut out = format!("<chord root=\"{}\"", escape_xml(&chord.root));
+    let structure = match chord.quality.as_str() {
+        "" => None,
+        "m" => Some("minor"),
+        "7" => Some("dom7"),
+        quality => Some(quality)
+    };
+    if let Some(structure) = structure {
+        out.push_str(&format!(" structure=\"{}\"", escape_xml(structure)));
+    }
+    if let Some(ref bass) = chord.bass {
+        out.push_str(&format!(" bass=\"{}\"", escape_xml(bass)));
+    }
+    out.push_str("/>");
+    out
+}
+
+fn lang_attribute(lang: Option<&str>) -> String {
+    lang.map(|lang| format!(" lang=\"{}\"", escape_xml(lang)))
+        .unwrap_or_default()
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    children(node, name).next()
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &str
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Trimmed text content, `None` when empty
+fn text(node: Node) -> Option<String> {
+    let text: String = node
+        .descendants()
+        .filter_map(|n| n.text().filter(|_| n.is_text()))
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SongCategory;
+
+    const AMAZING_GRACE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song xmlns="http://openlyrics.info/namespace/2009/song" version="0.9">
+  <properties>
+    <titles>
+      <title lang="en">Amazing Grace</title>
+      <title lang="ru">О, благодать</title>
+      <title>New Britain</title>
+    </titles>
+    <authors>
+      <author type="words">John Newton</author>
+      <author type="music">Traditional</author>
+      <author type="translation" lang="ru">Smith &amp; Co</author>
+    </authors>
+    <copyright>Public Domain</copyright>
+    <ccliNo>22025</ccliNo>
+    <released>1779</released>
+    <key>G</key>
+    <tempo type="bpm">80</tempo>
+    <verseOrder>v1 c v2 c</verseOrder>
+    <themes>
+      <theme>Salvation</theme>
+      <theme>Grace</theme>
+    </themes>
+  </properties>
+  <lyrics>
+    <verse name="v1" lang="en">
+      <lines><chord name="G"/>Amazing grace, how <chord name="C"/>sweet<br/>
+        That saved a <chord root="D" structure="dom7"/>wretch</lines>
+    </verse>
+    <verse name="c" lang="en">
+      <lines>Chorus <chord name="Em"/>line<comment>softly</comment></lines>
+    </verse>
+    <verse name="v2" lang="en">
+      <lines><tag name="it">Twas</tag> grace</lines>
+    </verse>
+    <verse name="v1" lang="ru">
+      <lines>О, благодать</lines>
+    </verse>
+  </lyrics>
+</song>
+"#;
+
+    fn chords(line: &SongLine) -> Vec<(usize, String)> {
+        line.chords
+            .iter()
+            .map(|c| (c.position, c.chord.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_metadata() {
+        let imported = OpenLyrics::import(AMAZING_GRACE).unwrap();
+        let song = &imported.song;
+
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.title_alt.as_deref(), Some("New Britain"));
+        assert_eq!(song.author_lyrics.as_deref(), Some("John Newton"));
+        assert_eq!(song.author_music.as_deref(), Some("Traditional"));
+        assert_eq!(song.translator.as_deref(), Some("Smith & Co"));
+        assert_eq!(song.copyright.as_deref(), Some("Public Domain"));
+        assert_eq!(song.year_written, Some(1779));
+        assert_eq!(song.original_key.as_deref(), Some("G"));
+        assert_eq!(song.tempo, Some(80));
+        assert_eq!(song.categories, vec![SongCategory::Salvation]);
+        assert_eq!(imported.themes, vec!["Salvation", "Grace"]);
+        assert_eq!(imported.ccli_number.as_deref(), Some("22025"));
+        assert_eq!(imported.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_import_verses_in_order() {
+        let imported = OpenLyrics::import(AMAZING_GRACE).unwrap();
+        let sections = &imported.parsed.sections;
+
+        assert_eq!(sections.len(), 4);
+        assert_eq!(sections[0].section_type, SongSectionType::Verse);
+        assert_eq!(sections[0].label.as_deref(), Some("1"));
+        assert_eq!(sections[0].lines[0].text, "Amazing grace, how sweet");
+        assert_eq!(
+            chords(&sections[0].lines[0]),
+            vec![(0, "G".to_string()), (19, "C".to_string())]
+        );
+        assert_eq!(sections[0].lines[1].text, "That saved a wretch");
+        assert_eq!(chords(&sections[0].lines[1]), vec![(13, "D7".to_string())]);
+        assert_eq!(sections[1].section_type, SongSectionType::Chorus);
+        assert_eq!(sections[1].lines[0].text, "Chorus line");
+        assert_eq!(sections[2].lines[0].text, "Twas grace");
+        assert_eq!(sections[3].recall, Some(1));
+
+        assert!(imported.song.content.contains("{title: Amazing Grace}"));
+        assert!(
+            imported
+                .song
+                .content
+                .contains("[G]Amazing grace, how [C]sweet")
+        );
+        assert!(imported.song.content.contains("{chorus}"));
+    }
+
+    #[test]
+    fn test_import_translations() {
+        let imported = OpenLyrics::import(AMAZING_GRACE).unwrap();
+
+        assert_eq!(imported.translations.len(), 1);
+        let translation = &imported.translations[0];
+        assert_eq!(translation.language, "ru");
+        assert_eq!(translation.title.as_deref(), Some("О, благодать"));
+        assert_eq!(translation.parsed.sections.len(), 1);
+        assert_eq!(translation.parsed.sections[0].lines[0].text, "О, благодать");
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(matches!(
+            OpenLyrics::import("<song>"),
+            Err(ImportError::Xml(_))
+        ));
+        assert_eq!(
+            OpenLyrics::import("<html/>").unwrap_err(),
+            ImportError::UnexpectedRoot("html".into())
+        );
+        assert_eq!(
+            OpenLyrics::import("<song><properties/></song>").unwrap_err(),
+            ImportError::MissingElement("lyrics")
+        );
+        assert_eq!(
+            OpenLyrics::import("<song><lyrics/></song>").unwrap_err(),
+            ImportError::MissingElement("title")
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let imported = OpenLyrics::import(AMAZING_GRACE).unwrap();
+        let xml = OpenLyrics::export(&imported);
+
+        assert!(xml.contains("<title lang=\"en\">Amazing Grace</title>"));
+        assert!(xml.contains("<title lang=\"ru\">О, благодать</title>"));
+        assert!(xml.contains("<author type=\"translation\" lang=\"ru\">Smith &amp; Co</author>"));
+        assert!(xml.contains("<ccliNo>22025</ccliNo>"));
+        assert!(xml.contains("<tempo type=\"bpm\">80</tempo>"));
+        assert!(xml.contains("<verseOrder>v1 c1 v2 c1</verseOrder>"));
+        assert!(xml.contains(
+            "<lines><chord root=\"G\"/>Amazing grace, how <chord root=\"C\"/>sweet<br/>"
+        ));
+        assert!(xml.contains("That saved a <chord root=\"D\" structure=\"dom7\"/>wretch"));
+        assert!(xml.contains("<chord root=\"E\" structure=\"minor\"/>line"));
+        assert!(xml.contains("<verse name=\"v1\" lang=\"ru\">"));
+        assert_eq!(xml.matches("<verse ").count(), 4);
+    }
+
+    #[test]
+    fn test_export_keeps_translation_verse_names() {
+        let xml = AMAZING_GRACE.replace(
+            "<verse name=\"v1\" lang=\"ru\">\n      <lines>О, благодать</lines>",
+            "<verse name=\"c\" lang=\"ru\">\n      <lines>Припев</lines>\n    </verse>\n    \
+             <verse name=\"v2\" lang=\"ru\">\n      <lines>Благодать</lines>"
+        );
+        let imported = OpenLyrics::import(&xml).unwrap();
+        assert_eq!(imported.translator_language.as_deref(), Some("ru"));
+
+        let xml = OpenLyrics::export(&imported);
+        assert!(xml.contains("<verseOrder>v1 c1 v2 c1</verseOrder>"));
+        assert!(xml.contains("<verse name=\"c1\" lang=\"ru\">"));
+        assert!(xml.contains("<verse name=\"v2\" lang=\"ru\">"));
+        assert!(!xml.contains("<verse name=\"v1\" lang=\"ru\">"));
+    }
+
+    #[test]
+    fn test_untyped_authors_wrote_lyrics() {
+        let xml = AMAZING_GRACE.replace(
+            "<author type=\"words\">John Newton</author>\n      <author type=\"music\">Traditional</author>",
+            "<author>John Newton</author>"
+        );
+        let imported = OpenLyrics::import(&xml).unwrap();
+        assert_eq!(imported.song.author_lyrics.as_deref(), Some("John Newton"));
+        assert_eq!(imported.song.author_music, None);
+
+        let xml = OpenLyrics::export(&imported);
+        assert_eq!(xml.matches("John Newton").count(), 1);
+    }
+
+    #[test]
+    fn test_export_chord_attributes() {
+        let line = SongLine {
+            text:   "Glory".to_string(),
+            chords: ["Am7/G", "Bb", "F#m"]
+                .into_iter()
+                .enumerate()
+                .map(|(position, chord)| PositionedChord {
+                    position,
+                    chord: Chord::parse(chord).unwrap()
+                })
+                .collect()
+        };
+        assert_eq!(
+            write_line(&line),
+            "<chord root=\"A\" structure=\"m7\" bass=\"G\"/>G<chord root=\"Bb\"/>l\
+             <chord root=\"F#\" structure=\"minor\"/>ory"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let imported = OpenLyrics::import(AMAZING_GRACE).unwrap();
+        let again = OpenLyrics::import(&OpenLyrics::export(&imported)).unwrap();
+
+        assert_eq!(again.song.title, imported.song.title);
+        assert_eq!(again.song.author_lyrics, imported.song.author_lyrics);
+        assert_eq!(again.themes, imported.themes);
+        assert_eq!(again.translations.len(), 1);
+
+        let sections = again.parsed.sections.iter().zip(&imported.parsed.sections);
+        for (again, original) in sections {
+            assert_eq!(again.section_type, original.section_type);
+            assert_eq!(again.lines, original.lines);
+            assert_eq!(again.recall, original.recall);
+        }
+    }
+}
@@ -0,0 +1,520 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! OpenSong import and export
+//!
+//! OpenSong keeps metadata in flat XML elements and the song in a single
+//! `<lyrics>` block: `[V1]` starts a section, lines starting with `.` hold
+//! chords for the lyric line below (which starts with a space), `;` starts
+//! a comment. Lines starting with a digit belong to the numbered verse of
+//! the current section, so `[V]` with `1...` and `2...` lines defines `V1`
+//! and `V2` sharing chord lines. `<presentation>` is the performance order.
+
+use roxmltree::{Document, Node};
+
+use super::{
+    Chord, CreateSong, ImportError, InterchangeSong, PositionedChord, SongLine, SongSection,
+    SongSectionType,
+    interchange::{
+        NamedSection, arrange, escape_xml, lyrics_song, section_names, theme_categories
+    }
+};
+
+/// OpenSong XML converter
+pub struct OpenSong;
+
+impl OpenSong {
+    /// Read song from an OpenSong file
+    pub fn import(xml: &str) -> Result<InterchangeSong, ImportError> {
+        let document = Document::parse(xml).map_err(|e| ImportError::Xml(e.to_string()))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "song" {
+            return Err(ImportError::UnexpectedRoot(
+                root.tag_name().name().to_string()
+            ));
+        }
+
+        let field = |name: &str| {
+            root.children()
+                .find(|child| child.is_element() && child.tag_name().name() == name)
+                .and_then(text)
+        };
+
+        let title = field("title").ok_or(ImportError::MissingElement("title"))?;
+        let order: Vec<String> = field("presentation")
+            .map(|order| order.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        let themes: Vec<String> = ["theme", "alttheme"]
+            .into_iter()
+            .filter_map(field)
+            .flat_map(|themes| {
+                themes
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|theme| !theme.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let defined = read_lyrics(&field("lyrics").unwrap_or_default());
+        let mut parsed = lyrics_song(Some(title.clone()), arrange(&defined, &order));
+        parsed.key = field("key");
+        parsed.capo = field("capo").and_then(|capo| capo.parse().ok());
+        parsed.tempo = field("tempo").and_then(|tempo| tempo.parse().ok());
+        parsed.time_signature = field("time_sig");
+
+        let song = CreateSong {
+            songbook_id: None,
+            number: field("hymn_number").and_then(|number| number.parse().ok()),
+            title,
+            title_alt: field("aka"),
+            author_lyrics: field("author"),
+            author_music: None,
+            translator: None,
+            year_written: None,
+            copyright: field("copyright"),
+            original_key: parsed.key.clone(),
+            tempo: parsed.tempo,
+            time_signature: parsed.time_signature.clone(),
+            content: String::new(),
+            categories: theme_categories(&themes),
+            tag_ids: Vec::new(),
+            source_url: None
+        };
+
+        Ok(InterchangeSong {
+            ccli_number: field("ccli"),
+            themes,
+            ..InterchangeSong::new(song, parsed)
+        })
+    }
+
+    /// Write song as an OpenSong file
+    ///
+    /// Translations are not part of the format and are left out.
+    pub fn export(song: &InterchangeSong) -> String {
+        let meta = &song.song;
+        let names: Vec<Option<String>> = section_names(&song.parsed.sections, letter);
+        let order: Vec<&str> = names.iter().flatten().map(String::as_str).collect();
+
+        let mut authors: Vec<&str> = Vec::new();
+        for author in [&meta.author_lyrics, &meta.author_music]
+            .into_iter()
+            .flatten()
+        {
+            if !authors.contains(&author.as_str()) {
+                authors.push(author);
+            }
+        }
+        let themes = if song.themes.is_empty() {
+            meta.categories
+                .iter()
+                .map(|c| c.name_en().to_string())
+                .collect()
+        } else {
+            song.themes.clone()
+        };
+
+        let fields = [
+            ("title", Some(meta.title.clone())),
+            ("aka", meta.title_alt.clone()),
+            ("author", (!authors.is_empty()).then(|| authors.join(", "))),
+            ("copyright", meta.copyright.clone()),
+            ("hymn_number", meta.number.map(|n| n.to_string())),
+            ("presentation", (!order.is_empty()).then(|| order.join(" "))),
+            ("ccli", song.ccli_number.clone()),
+            ("capo", song.parsed.capo.map(|c| c.to_string())),
+            (
+                "key",
+                meta.original_key
+                    .clone()
+                    .or_else(|| song.parsed.key.clone())
+            ),
+            (
+                "tempo",
+                meta.tempo.or(song.parsed.tempo).map(|t| t.to_string())
+            ),
+            ("time_sig", meta.time_signature.clone()),
+            ("theme", (!themes.is_empty()).then(|| themes.join("; ")))
+        ];
+
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<song>\n");
+        for (name, value) in fields {
+            if let Some(value) = value {
+                out.push_str(&format!("  <{0}>{1}</{0}>\n", name, escape_xml(&value)));
+            }
+        }
+
+        let mut lyrics = String::new();
+        for (section, name) in song.parsed.sections.iter().zip(&names) {
+            let Some(name) = name.as_ref().filter(|_| section.recall.is_none()) else {
+                continue;
+            };
+            if !lyrics.is_empty() {
+                lyrics.push('\n');
+            }
+            lyrics.push_str(&format!("[{}]\n", name));
+            for line in &section.lines {
+                if !line.chords.is_empty() {
+                    lyrics.push_str(&format!("{}\n", chord_line(line)));
+                }
+                lyrics.push_str(&format!(" {}\n", line.text));
+            }
+        }
+        out.push_str(&format!("  <lyrics>{}</lyrics>\n", escape_xml(&lyrics)));
+        out.push_str("</song>\n");
+        out
+    }
+}
+
+/// Sections defined in a `<lyrics>` block, in order of appearance
+fn read_lyrics(lyrics: &str) -> Vec<NamedSection> {
+    let mut defined: Vec<NamedSection> = Vec::new();
+    let mut base = "V".to_string();
+    // Chords of the last `.` line and whether a lyric line used them
+    let mut chords: Vec<(usize, Chord)> = Vec::new();
+    let mut used = false;
+
+    for line in lyrics.lines() {
+        let line = line.trim_end();
+        let mut chars = line.chars();
+        let first = chars.next();
+
+        if matches!(first, Some('[') | Some('.')) && !chords.is_empty() && !used {
+            section(&mut defined, &base).lines.push(chord_only(&chords));
+        }
+
+        match first {
+            None | Some(';') => {}
+            Some('[') if line.contains(']') => {
+                let end = line.find(']').expect("checked above");
+                base = line[1..end].trim().to_string();
+                chords.clear();
+                section(&mut defined, &base);
+            }
+            Some('.') => {
+                chords = chord_columns(chars.as_str());
+                used = false;
+            }
+            Some(first) => {
+                let number = first.to_digit(10);
+                let (name, text) = match number {
+                    Some(number) => (format!("{}{}", base, number), chars.as_str()),
+                    None if first == ' ' => (base.clone(), chars.as_str()),
+                    None => (base.clone(), line)
+                };
+                let text = text.trim_end().to_string();
+                let len = text.chars().count();
+                let positioned = chords
+                    .iter()
+                    .map(|(column, chord)| PositionedChord {
+                        position: (*column).min(len),
+                        chord:    chord.clone()
+                    })
+                    .collect();
+
+                section(&mut defined, &name).lines.push(SongLine {
+                    text,
+                    chords: positioned
+                });
+                // A chord line serves every numbered verse below it
+                if number.is_some() {
+                    used = true;
+                } else {
+                    chords.clear();
+                }
+            }
+        }
+    }
+
+    if !chords.is_empty() && !used {
+        section(&mut defined, &base).lines.push(chord_only(&chords));
+    }
+    defined.retain(|named| !named.section.lines.is_empty());
+    defined
+}
+
+/// Section with the given name, created on first use
+fn section<'a>(defined: &'a mut Vec<NamedSection>, name: &str) -> &'a mut SongSection {
+    let index = match defined.iter().position(|named| named.name == name) {
+        Some(index) => index,
+        None => {
+            let (section_type, label) = section_kind(name);
+            defined.push(NamedSection {
+                name:    name.to_string(),
+                section: SongSection {
+                    section_type,
+                    label,
+                    lines: Vec::new(),
+                    recall: None,
+                    grid: None
+                }
+            });
+            defined.len() - 1
+        }
+    };
+    &mut defined[index].section
+}
+
+/// Chords of a `.` line with their columns after the dot
+fn chord_columns(line: &str) -> Vec<(usize, Chord)> {
+    let mut columns = Vec::new();
+    let mut start = None;
+    let chars: Vec<char> = line.chars().collect();
+
+    for (column, &c) in chars.iter().chain(std::iter::once(&' ')).enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(column),
+            (true, Some(from)) => {
+                let word: String = chars[from..column].iter().collect();
+                let word = word.trim_start_matches('(').trim_end_matches(')');
+                if let Some(chord) = Chord::parse(word).filter(Chord::is_valid) {
+                    columns.push((from, chord));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    columns
+}
+
+/// Line of chords without lyrics below
+fn chord_only(chords: &[(usize, Chord)]) -> SongLine {
+    SongLine {
+        text:   String::new(),
+        chords: chords
+            .iter()
+            .map(|(_, chord)| PositionedChord {
+                position: 0,
+                chord:    chord.clone()
+            })
+            .collect()
+    }
+}
+
+/// `.` line with chords above their lyric positions, moved right where
+/// they would touch
+fn chord_line(line: &SongLine) -> String {
+    let mut chords: Vec<&PositionedChord> = line.chords.iter().collect();
+    chords.sort_by_key(|chord| chord.position);
+
+    let mut out = String::from(".");
+    let mut width = 0;
+    for positioned in chords {
+        let column = if width == 0 {
+            positioned.position
+        } else {
+            positioned.position.max(width + 1)
+        };
+        let chord = positioned.chord.to_string();
+        out.extend(std::iter::repeat_n(' ', column - width));
+        out.push_str(&chord);
+        width = column + chord.chars().count();
+    }
+    out
+}
+
+/// Section type and label of a marker like `V1`, `C` or `T`
+fn section_kind(name: &str) -> (SongSectionType, Option<String>) {
+    let mut chars = name.chars();
+    let section_type = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some('V') => SongSectionType::Verse,
+        Some('C') => SongSectionType::Chorus,
+        Some('B') => SongSectionType::Bridge,
+        Some('P') => SongSectionType::PreChorus,
+        Some('T') => SongSectionType::Tag,
+        Some('I') => SongSectionType::Intro,
+        Some('E') => SongSectionType::Ending,
+        _ => SongSectionType::Other
+    };
+    let label = chars.as_str().trim();
+    (section_type, (!label.is_empty()).then(|| label.to_string()))
+}
+
+/// OpenSong section marker letter
+fn letter(section_type: SongSectionType) -> char {
+    match section_type {
+        SongSectionType::Verse => 'V',
+        SongSectionType::Chorus => 'C',
+        SongSectionType::Bridge => 'B',
+        SongSectionType::PreChorus => 'P',
+        SongSectionType::Tag => 'T',
+        SongSectionType::Intro => 'I',
+        SongSectionType::Ending | SongSectionType::Outro => 'E',
+        _ => 'O'
+    }
+}
+
+/// Text content kept as is, `None` when blank
+fn text(node: Node) -> Option<String> {
+    let text: String = node
+        .descendants()
+        .filter_map(|n| n.text().filter(|_| n.is_text()))
+        .collect();
+    (!text.trim().is_empty()).then(|| {
+        if node.tag_name().name() == "lyrics" {
+            text
+        } else {
+            text.trim().to_string()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SongCategory;
+
+    const AMAZING_GRACE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song>
+  <title>Amazing Grace</title>
+  <author>John Newton</author>
+  <copyright>Public Domain</copyright>
+  <hymn_number>42</hymn_number>
+  <presentation>V1 C V2 C</presentation>
+  <ccli>22025</ccli>
+  <capo print="false">2</capo>
+  <key>G</key>
+  <aka>New Britain</aka>
+  <time_sig>3/4</time_sig>
+  <theme>Salvation; Grace</theme>
+  <alttheme>Christmas</alttheme>
+  <lyrics>[V]
+;Both verses share the chords
+.G          C
+1Amazing grace, how sweet
+2Twas grace that taught
+.D
+1That saved a wretch
+2My heart to fear
+
+[C]
+.Em     D
+ Chorus line
+.G  D  G
+</lyrics>
+</song>
+"#;
+
+    fn chords(line: &SongLine) -> Vec<(usize, String)> {
+        line.chords
+            .iter()
+            .map(|c| (c.position, c.chord.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_metadata() {
+        let imported = OpenSong::import(AMAZING_GRACE).unwrap();
+        let song = &imported.song;
+
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.title_alt.as_deref(), Some("New Britain"));
+        assert_eq!(song.author_lyrics.as_deref(), Some("John Newton"));
+        assert_eq!(song.number, Some(42));
+        assert_eq!(song.original_key.as_deref(), Some("G"));
+        assert_eq!(song.time_signature.as_deref(), Some("3/4"));
+        assert_eq!(imported.parsed.capo, Some(2));
+        assert_eq!(imported.ccli_number.as_deref(), Some("22025"));
+        assert_eq!(imported.themes, vec!["Salvation", "Grace", "Christmas"]);
+        assert_eq!(
+            song.categories,
+            vec![SongCategory::Salvation, SongCategory::Christmas]
+        );
+    }
+
+    #[test]
+    fn test_import_lyrics() {
+        let imported = OpenSong::import(AMAZING_GRACE).unwrap();
+        let sections = &imported.parsed.sections;
+
+        assert_eq!(sections.len(), 4);
+        assert_eq!(sections[0].section_type, SongSectionType::Verse);
+        assert_eq!(sections[0].label.as_deref(), Some("1"));
+        assert_eq!(sections[0].lines[0].text, "Amazing grace, how sweet");
+        assert_eq!(
+            chords(&sections[0].lines[0]),
+            vec![(0, "G".to_string()), (11, "C".to_string())]
+        );
+        assert_eq!(sections[0].lines[1].text, "That saved a wretch");
+        assert_eq!(sections[2].lines[0].text, "Twas grace that taught");
+        assert_eq!(
+            chords(&sections[2].lines[0]),
+            vec![(0, "G".to_string()), (11, "C".to_string())]
+        );
+
+        let chorus = &sections[1];
+        assert_eq!(chorus.section_type, SongSectionType::Chorus);
+        assert_eq!(chorus.lines[0].text, "Chorus line");
+        assert_eq!(
+            chords(&chorus.lines[0]),
+            vec![(0, "Em".to_string()), (7, "D".to_string())]
+        );
+        assert_eq!(chorus.lines[1].text, "");
+        assert_eq!(chorus.lines[1].chords.len(), 3);
+        assert_eq!(sections[3].recall, Some(1));
+        assert!(imported.song.content.contains("{chorus}"));
+    }
+
+    #[test]
+    fn test_import_without_presentation() {
+        let xml = "<song><title>T</title><lyrics>[C]\n Glory\n[V1]\n Amen</lyrics></song>";
+        let imported = OpenSong::import(xml).unwrap();
+        let sections = &imported.parsed.sections;
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].section_type, SongSectionType::Chorus);
+        assert_eq!(sections[1].lines[0].text, "Amen");
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(matches!(
+            OpenSong::import("<song"),
+            Err(ImportError::Xml(_))
+        ));
+        assert_eq!(
+            OpenSong::import("<lyrics/>").unwrap_err(),
+            ImportError::UnexpectedRoot("lyrics".into())
+        );
+        assert_eq!(
+            OpenSong::import("<song><lyrics> x</lyrics></song>").unwrap_err(),
+            ImportError::MissingElement("title")
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let imported = OpenSong::import(AMAZING_GRACE).unwrap();
+        let xml = OpenSong::export(&imported);
+
+        assert!(xml.contains("<title>Amazing Grace</title>"));
+        assert!(xml.contains("<presentation>V1 C1 V2 C1</presentation>"));
+        assert!(xml.contains("<theme>Salvation; Grace; Christmas</theme>"));
+        assert!(xml.contains("<capo>2</capo>"));
+        assert!(xml.contains("[V1]\n.G          C\n Amazing grace, how sweet\n"));
+        assert!(xml.contains("[C1]\n.Em     D\n Chorus line\n.G D G\n \n"));
+        assert_eq!(xml.matches("[C1]").count(), 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let imported = OpenSong::import(AMAZING_GRACE).unwrap();
+        let again = OpenSong::import(&OpenSong::export(&imported)).unwrap();
+
+        assert_eq!(again.song.title, imported.song.title);
+        assert_eq!(again.song.categories, imported.song.categories);
+        assert_eq!(again.parsed.capo, imported.parsed.capo);
+
+        let sections = again.parsed.sections.iter().zip(&imported.parsed.sections);
+        for (again, original) in sections {
+            assert_eq!(again.section_type, original.section_type);
+            assert_eq!(again.lines, original.lines);
+            assert_eq!(again.recall, original.recall);
+        }
+    }
+}
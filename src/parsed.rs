@@ -3,10 +3,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::SongSection;
+use super::{Chord, ChordDefinition, ChordGrid, GridCell, GridRow, Key, SongSection, detect_key};
 
 /// Fully parsed song structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct ParsedSong {
     pub title:          Option<String>,
@@ -17,5 +17,132 @@ pub struct ParsedSong {
     pub tempo:          Option<i32>,
     pub time_signature: Option<String>,
     pub capo:           Option<i32>,
+    /// Custom chord shapes from `{define}` and `{chord}`
+    #[serde(default)]
+    pub definitions:    Vec<ChordDefinition>,
     pub sections:       Vec<SongSection>
 }
+
+impl ParsedSong {
+    /// Sections in the order they are performed
+    ///
+    /// Recalled sections (`{chorus}`) are replaced with the section they
+    /// point to, so the result can be rendered as slides or pages directly.
+    pub fn performance_order(&self) -> Vec<&SongSection> {
+        self.sections
+            .iter()
+            .map(|section| {
+                section
+                    .recall
+                    .and_then(|index| self.sections.get(index))
+                    .unwrap_or(section)
+            })
+            .collect()
+    }
+
+    /// Chords in performance order, including chord grids
+    pub fn chords(&self) -> Vec<&Chord> {
+        let mut chords = Vec::new();
+        for section in self.performance_order() {
+            for line in &section.lines {
+                chords.extend(line.chords.iter().map(|c| &c.chord));
+            }
+            for row in section.grid.iter().flat_map(|grid| &grid.rows) {
+                for cell in row.bars.iter().flat_map(|bar| &bar.cells) {
+                    if let GridCell::Chords(cell_chords) = cell {
+                        chords.extend(cell_chords);
+                    }
+                }
+            }
+        }
+        chords
+    }
+
+    /// Replace every chord in lyrics and chord grids
+    ///
+    /// Grid lines are rewritten in place and the grid model is rebuilt.
+    /// Definitions are left untouched.
+    pub fn map_chords(&mut self, mut f: impl FnMut(&Chord) -> Chord) {
+        for section in &mut self.sections {
+            for line in &mut section.lines {
+                for positioned in &mut line.chords {
+                    positioned.chord = f(&positioned.chord);
+                }
+            }
+
+            if section.grid.is_some() {
+                for line in &mut section.lines {
+                    line.text = GridRow::map_chords(&line.text, &mut f);
+                }
+                section.grid = Some(ChordGrid::parse(
+                    section.lines.iter().map(|line| line.text.as_str())
+                ));
+            }
+        }
+    }
+
+    /// Key from `{key}`, or the best detected key when it is missing
+    pub fn estimated_key(&self) -> Option<Key> {
+        self.key
+            .as_deref()
+            .and_then(Key::parse)
+            .or_else(|| detect_key(self).first().map(|c| c.key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ChordProParser, SongSectionType};
+
+    #[test]
+    fn test_chords_in_performance_order() {
+        let content = "{soc}\n[G]Chorus [D]line\n{eoc}\n{sov}\n[Em]Verse\n{eov}\n{chorus}";
+        let song = ChordProParser::parse(content);
+        let chords: Vec<String> = song.chords().iter().map(|c| c.to_string()).collect();
+        assert_eq!(chords, ["G", "D", "Em", "G", "D"]);
+    }
+
+    #[test]
+    fn test_map_chords() {
+        let content = "[C]Hello [G/B]world\n\n{start_of_grid}\n| C . | G . |\n{end_of_grid}";
+        let mut song = ChordProParser::parse(content);
+        song.map_chords(|c| c.transpose(2, false));
+
+        let chords: Vec<String> = song.chords().iter().map(|c| c.to_string()).collect();
+        assert_eq!(chords, ["D", "A/C#", "D", "A"]);
+        assert_eq!(song.sections[1].lines[0].text, "| D . | A . |");
+        assert_eq!(song.sections[0].lines[0].chords[1].position, 6);
+    }
+
+    #[test]
+    fn test_estimated_key() {
+        let song = ChordProParser::parse("{key: Bb}\n[C]Hello [G]world");
+        assert_eq!(song.estimated_key().unwrap().to_string(), "Bb");
+
+        let song = ChordProParser::parse("[A]Hello [E]world [A]again");
+        assert_eq!(song.estimated_key().unwrap().to_string(), "A");
+
+        assert!(ChordProParser::parse("Hello").estimated_key().is_none());
+    }
+
+    #[test]
+    fn test_performance_order_expands_recalls() {
+        let content = "{soc}\nChorus\n{eoc}\n{sov}\nVerse\n{eov}\n{chorus}\n{chorus}";
+        let song = ChordProParser::parse(content);
+        let order = song.performance_order();
+
+        assert_eq!(order.len(), 4);
+        assert_eq!(order[0].section_type, SongSectionType::Chorus);
+        assert_eq!(order[1].lines[0].text, "Verse");
+        assert_eq!(order[2].lines[0].text, "Chorus");
+        assert_eq!(order[3].lines[0].text, "Chorus");
+    }
+
+    #[test]
+    fn test_performance_order_without_recalls() {
+        let song = ChordProParser::parse("Line one\n\nLine two");
+        let order = song.performance_order();
+        assert_eq!(order.len(), 1);
+        assert_eq!(order[0].lines.len(), 3);
+    }
+}
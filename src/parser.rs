@@ -10,24 +10,77 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 
-use super::{Chord, ParsedSong, PositionedChord, SongLine, SongSection, SongSectionType};
+use super::{
+    Chord, ChordDefinition, ChordGrid, Diagnostic, DiagnosticCode, Directive, LineNode,
+    ParsedSong, PositionedChord, Segment, SongLine, SongSection, SongSectionType, Span,
+    SyntaxTree,
+    heading::Heading,
+    syntax::{rfind_unescaped, unescape}
+};
 
 /// Regex patterns for ChordPro parsing
 static DIRECTIVE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\{(\w+)(?::\s*([^}]*))?\}").unwrap());
 
-static CHORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]+)\]").unwrap());
+/// Chord or escaped character in lyrics
+static CHORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\(.)|\[[^\]]+\]").unwrap());
 
-static SECTION_START_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"(?i)\{(start_of_|so)(verse|chorus|bridge|tab|grid|abc|ly|textblock)(?::\s*([^}]*))?\}"
-    )
-    .unwrap()
-});
+/// Lyrics without chords and escapes
+fn strip_lyrics(line: &str) -> String {
+    CHORD_RE
+        .replace_all(line, |caps: &regex::Captures| {
+            caps.get(1).map_or("", |c| c.as_str()).to_string()
+        })
+        .into_owned()
+}
 
-static SECTION_END_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)\{(end_of_|eo)(verse|chorus|bridge|tab|grid|abc|ly|textblock)\}").unwrap()
-});
+/// ChordPro directives that are valid but not represented in [`ParsedSong`]
+const UNSUPPORTED_DIRECTIVES: &[&str] = &[
+    "album",
+    "arranger",
+    "chordcolour",
+    "chordfont",
+    "chordsize",
+    "colb",
+    "col",
+    "column_break",
+    "columns",
+    "copyright",
+    "duration",
+    "footersize",
+    "grid",
+    "highlight",
+    "image",
+    "lyricist",
+    "meta",
+    "new_page",
+    "new_physical_page",
+    "no_grid",
+    "np",
+    "npp",
+    "pagetype",
+    "sorttitle",
+    "tabfont",
+    "tabsize",
+    "textcolour",
+    "textfont",
+    "textsize",
+    "titles",
+    "transpose",
+    "year"
+];
+
+/// ChordPro variant of the input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dialect {
+    /// Standard ChordPro
+    #[default]
+    ChordPro,
+    /// ChordPro as exported by OnSong, SongBook and Ultimate Guitar:
+    /// `Title: ...` headers without braces, `Chorus:`, `Припев:` and
+    /// `[Verse 2]` heading lines, silently ignored `{x_...}` directives
+    Compat
+}
 
 /// ChordPro format parser
 pub struct ChordProParser;
@@ -35,144 +88,169 @@ pub struct ChordProParser;
 impl ChordProParser {
     /// Parse ChordPro content into structured song
     pub fn parse(content: &str) -> ParsedSong {
-        let mut song = ParsedSong {
-            title:          None,
-            subtitle:       None,
-            artist:         None,
-            composer:       None,
-            key:            None,
-            tempo:          None,
-            time_signature: None,
-            capo:           None,
-            sections:       Vec::new()
-        };
-
-        let mut current_section: Option<SongSection> = None;
+        Self::parse_with_diagnostics(content).0
+    }
 
-        for line in content.lines() {
-            let trimmed = line.trim();
+    /// Parse ChordPro content, collecting problems instead of silently
+    /// dropping them
+    ///
+    /// The returned song is identical to [`ChordProParser::parse`].
+    pub fn parse_with_diagnostics(content: &str) -> (ParsedSong, Vec<Diagnostic>) {
+        Self::parse_tree(&SyntaxTree::parse(content))
+    }
 
-            if trimmed.is_empty() {
-                if let Some(ref mut section) = current_section {
-                    section.lines.push(SongLine {
-                        text:   String::new(),
-                        chords: Vec::new()
-                    });
-                }
-                continue;
-            }
+    /// Build structured song from a lossless syntax tree
+    pub fn parse_tree(tree: &SyntaxTree) -> (ParsedSong, Vec<Diagnostic>) {
+        Self::parse_tree_dialect(tree, Dialect::ChordPro)
+    }
 
-            if let Some(caps) = SECTION_START_RE.captures(trimmed) {
-                if let Some(section) = current_section.take()
-                    && !section.lines.is_empty()
-                {
-                    song.sections.push(section);
-                }
+    /// Parse content written in a ChordPro dialect
+    pub fn parse_dialect(content: &str, dialect: Dialect) -> ParsedSong {
+        Self::parse_dialect_with_diagnostics(content, dialect).0
+    }
 
-                let section_type = Self::parse_section_type(&caps[2]);
-                let label = caps.get(3).map(|m| m.as_str().trim().to_string());
+    /// Parse content written in a ChordPro dialect, collecting problems
+    pub fn parse_dialect_with_diagnostics(
+        content: &str,
+        dialect: Dialect
+    ) -> (ParsedSong, Vec<Diagnostic>) {
+        Self::parse_tree_dialect(&SyntaxTree::parse(content), dialect)
+    }
 
-                current_section = Some(SongSection {
-                    section_type,
-                    label,
-                    lines: Vec::new()
-                });
+    /// Build structured song from a syntax tree written in a dialect
+    pub fn parse_tree_dialect(
+        tree: &SyntaxTree,
+        dialect: Dialect
+    ) -> (ParsedSong, Vec<Diagnostic>) {
+        let mut builder = SongBuilder::new(dialect);
+
+        for (index, line) in tree.lines.iter().enumerate() {
+            let line_no = index + 1;
+            let column = line.indent.chars().count() + 1;
+            let line_span = Span::new(line_no, column, line.content().chars().count());
+
+            if builder.is_verbatim()
+                && line
+                    .directive()
+                    .is_none_or(|d| Self::section_end(&d.normalized_name()).is_none())
+            {
+                builder.verbatim_line(format!("{}{}{}", line.indent, line.node, line.trailing));
                 continue;
             }
 
-            if SECTION_END_RE.is_match(trimmed) {
-                if let Some(section) = current_section.take()
-                    && !section.lines.is_empty()
-                {
-                    song.sections.push(section);
+            match &line.node {
+                LineNode::Blank => builder.blank_line(),
+                LineNode::Comment(_) => {}
+                LineNode::Directive(directive) => {
+                    let value_span = Span::new(
+                        line_no,
+                        column + directive.value_offset(),
+                        directive.value.as_deref().map_or(0, |v| v.chars().count())
+                    );
+                    builder.directive(directive, line_span, value_span);
                 }
-                continue;
-            }
-
-            if let Some(caps) = DIRECTIVE_RE.captures(trimmed) {
-                let directive = caps[1].to_lowercase();
-                let value = caps.get(2).map(|m| m.as_str().trim().to_string());
-
-                match directive.as_str() {
-                    "title" | "t" => song.title = value,
-                    "subtitle" | "st" => song.subtitle = value,
-                    "artist" | "a" => song.artist = value,
-                    "composer" => song.composer = value,
-                    "key" => song.key = value,
-                    "tempo" => {
-                        song.tempo = value.and_then(|v| v.parse().ok());
-                    }
-                    "time" => song.time_signature = value,
-                    "capo" => song.capo = value.and_then(|v| v.parse().ok()),
-                    "c" | "comment" => {
-                        if let Some(ref mut section) = current_section
-                            && let Some(text) = value
-                        {
-                            section.lines.push(SongLine {
-                                text,
-                                chords: Vec::new()
-                            });
-                        }
+                LineNode::Lyrics(segments) => {
+                    if dialect == Dialect::Compat && builder.compat_line(segments, line_span) {
+                        continue;
                     }
-                    _ => {}
+                    let song_line =
+                        Self::parse_segments(segments, line_no, column, &mut builder.diagnostics);
+                    builder.lyrics(song_line);
                 }
-                continue;
-            }
-
-            let song_line = Self::parse_line(trimmed);
-
-            if let Some(ref mut section) = current_section {
-                section.lines.push(song_line);
-            } else if !song_line.text.is_empty() || !song_line.chords.is_empty() {
-                current_section = Some(SongSection {
-                    section_type: SongSectionType::Verse,
-                    label:        None,
-                    lines:        vec![song_line]
-                });
             }
         }
 
-        if let Some(section) = current_section
-            && !section.lines.is_empty()
-        {
-            song.sections.push(section);
-        }
-
-        song
+        builder.finish()
     }
 
-    /// Parse a single line with inline chords
-    fn parse_line(line: &str) -> SongLine {
+    /// Build a song line from lyrics segments
+    ///
+    /// `column` is the 1-based column where the first segment starts.
+    fn parse_segments(
+        segments: &[Segment],
+        line_no: usize,
+        column: usize,
+        diagnostics: &mut Vec<Diagnostic>
+    ) -> SongLine {
         let mut chords = Vec::new();
         let mut text = String::new();
-        let mut last_end = 0;
+        let mut offset = column;
 
-        for caps in CHORD_RE.captures_iter(line) {
-            let m = caps.get(0).unwrap();
-
-            text.push_str(&line[last_end..m.start()]);
-
-            let chord_str = &caps[1];
-            if let Some(chord) = Chord::parse(chord_str) {
-                chords.push(PositionedChord {
-                    position: text.chars().count(),
-                    chord
-                });
+        for segment in segments {
+            match segment {
+                Segment::Text(part) => {
+                    if let Some(idx) = rfind_unescaped(part, '[')
+                        && !part[idx..].contains(']')
+                    {
+                        diagnostics.push(Diagnostic::warning(
+                            DiagnosticCode::UnclosedChord,
+                            Span::new(
+                                line_no,
+                                offset + part[..idx].chars().count(),
+                                part[idx..].chars().count()
+                            ),
+                            "chord bracket is never closed"
+                        ));
+                    }
+                    text.push_str(&unescape(part));
+                }
+                Segment::Chord(chord_str) => {
+                    if let Some(chord) = Chord::parse(chord_str) {
+                        chords.push(PositionedChord {
+                            position: text.chars().count(),
+                            chord
+                        });
+                    } else if !chord_str.starts_with('*') {
+                        diagnostics.push(Diagnostic::error(
+                            DiagnosticCode::InvalidChord,
+                            Span::new(line_no, offset, segment.width()),
+                            format!("invalid chord '{}'", chord_str)
+                        ));
+                    }
+                }
             }
-
-            last_end = m.end();
+            offset += segment.width();
         }
 
-        text.push_str(&line[last_end..]);
-
         SongLine {
             text,
             chords
         }
     }
 
+    /// Section kind opened by a `{start_of_*}` directive name
+    pub(crate) fn section_start(name: &str) -> Option<String> {
+        if let Some(kind) = name.strip_prefix("start_of_") {
+            return Some(kind.to_string());
+        }
+        Self::short_section(name, "so")
+    }
+
+    /// Section kind closed by an `{end_of_*}` directive name
+    pub(crate) fn section_end(name: &str) -> Option<String> {
+        if let Some(kind) = name.strip_prefix("end_of_") {
+            return Some(kind.to_string());
+        }
+        Self::short_section(name, "eo")
+    }
+
+    /// Resolve `{sov}`, `{eoc}` and similar abbreviations
+    fn short_section(name: &str, prefix: &str) -> Option<String> {
+        let kind = match name.strip_prefix(prefix)? {
+            "v" => "verse",
+            "c" => "chorus",
+            "b" => "bridge",
+            "t" => "tab",
+            "g" => "grid",
+            kind @ ("verse" | "chorus" | "bridge" | "tab" | "grid" | "abc" | "ly"
+            | "textblock") => kind,
+            _ => return None
+        };
+        Some(kind.to_string())
+    }
+
     /// Parse section type from string
-    fn parse_section_type(s: &str) -> SongSectionType {
+    pub(crate) fn parse_section_type(s: &str) -> SongSectionType {
         match s.to_lowercase().as_str() {
             "verse" | "v" => SongSectionType::Verse,
             "chorus" | "c" => SongSectionType::Chorus,
@@ -183,6 +261,11 @@ impl ChordProParser {
             "interlude" => SongSectionType::Interlude,
             "tag" => SongSectionType::Tag,
             "ending" | "coda" => SongSectionType::Ending,
+            "tab" => SongSectionType::Tab,
+            "grid" => SongSectionType::Grid,
+            "abc" => SongSectionType::Abc,
+            "ly" => SongSectionType::Lilypond,
+            "textblock" => SongSectionType::Textblock,
             _ => SongSectionType::Other
         }
     }
@@ -207,7 +290,7 @@ impl ChordProParser {
                 continue;
             }
 
-            let plain = CHORD_RE.replace_all(trimmed, "");
+            let plain = strip_lyrics(trimmed);
             let plain = plain.trim();
 
             if !plain.is_empty() {
@@ -228,7 +311,7 @@ impl ChordProParser {
                 continue;
             }
 
-            let plain = CHORD_RE.replace_all(trimmed, "");
+            let plain = strip_lyrics(trimmed);
             let plain = plain.trim();
 
             if !plain.is_empty() {
@@ -266,9 +349,350 @@ impl ChordProParser {
     }
 }
 
+/// Directive for a `Name: value` header line of [`Dialect::Compat`]
+///
+/// Headers ChordPro has no directive for are kept as custom `x_` ones.
+fn header_directive(name: &str) -> Option<&'static str> {
+    let directive = match name.to_lowercase().as_str() {
+        "title" => "title",
+        "subtitle" => "subtitle",
+        "artist" | "author" | "by" => "artist",
+        "composer" => "composer",
+        "key" => "key",
+        "capo" => "capo",
+        "tempo" | "bpm" => "tempo",
+        "time" | "time signature" => "time",
+        "copyright" => "copyright",
+        "album" => "album",
+        "year" => "year",
+        "duration" => "duration",
+        "ccli" => "x_ccli",
+        "flow" => "x_flow",
+        "keywords" => "x_keywords",
+        _ => return None
+    };
+    Some(directive)
+}
+
+/// Section opened by an explicit `{start_of_*}` directive
+struct OpenSection {
+    kind: String,
+    span: Span
+}
+
+/// Accumulates parsed song state while walking the syntax tree
+struct SongBuilder {
+    song:            ParsedSong,
+    diagnostics:     Vec<Diagnostic>,
+    current_section: Option<SongSection>,
+    open_section:    Option<OpenSection>,
+    dialect:         Dialect,
+    /// No lyrics or sections yet, so `Title: ...` lines are metadata
+    in_header:       bool,
+    /// Span of the [`Dialect::Compat`] heading that opened the current section
+    heading:         Option<Span>
+}
+
+impl SongBuilder {
+    fn new(dialect: Dialect) -> Self {
+        Self {
+            song: ParsedSong {
+                title:          None,
+                subtitle:       None,
+                artist:         None,
+                composer:       None,
+                key:            None,
+                tempo:          None,
+                time_signature: None,
+                capo:           None,
+                definitions:    Vec::new(),
+                sections:       Vec::new()
+            },
+            diagnostics: Vec::new(),
+            current_section: None,
+            open_section: None,
+            dialect,
+            in_header: true,
+            heading: None
+        }
+    }
+
+    /// Push the current section if it has any lines
+    ///
+    /// A chorus heading without lyrics repeats the chorus, like `{chorus}`.
+    fn flush_section(&mut self) {
+        if let Some(span) = self.heading.take()
+            && let Some(section) = self.current_section.take_if(|section| {
+                section.section_type == SongSectionType::Chorus
+                    && section
+                        .lines
+                        .iter()
+                        .all(|line| line.text.is_empty() && line.chords.is_empty())
+            })
+        {
+            self.recall_chorus(section.label, span);
+            return;
+        }
+
+        if let Some(mut section) = self.current_section.take()
+            && !section.lines.is_empty()
+        {
+            if section.section_type == SongSectionType::Grid {
+                section.grid = Some(ChordGrid::parse(
+                    section.lines.iter().map(|line| line.text.as_str())
+                ));
+            }
+            self.song.sections.push(section);
+        }
+    }
+
+    /// Whether the open section keeps its content verbatim
+    fn is_verbatim(&self) -> bool {
+        self.current_section
+            .as_ref()
+            .is_some_and(|section| section.section_type.is_verbatim())
+    }
+
+    fn verbatim_line(&mut self, text: String) {
+        if let Some(ref mut section) = self.current_section {
+            section.lines.push(SongLine {
+                text,
+                chords: Vec::new()
+            });
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if let Some(ref mut section) = self.current_section {
+            section.lines.push(SongLine {
+                text:   String::new(),
+                chords: Vec::new()
+            });
+        }
+    }
+
+    fn lyrics(&mut self, song_line: SongLine) {
+        self.in_header = false;
+        if let Some(ref mut section) = self.current_section {
+            section.lines.push(song_line);
+        } else if !song_line.text.is_empty() || !song_line.chords.is_empty() {
+            self.current_section = Some(SongSection {
+                section_type: SongSectionType::Verse,
+                label:        None,
+                lines:        vec![song_line],
+                recall:       None,
+                grid:         None
+            });
+        }
+    }
+
+    /// Handle a header or heading line of [`Dialect::Compat`], returning
+    /// whether the line was consumed
+    fn compat_line(&mut self, segments: &[Segment], span: Span) -> bool {
+        let (text, bracketed) = match segments {
+            [Segment::Text(text)] => (text.as_str(), false),
+            [Segment::Chord(text)] => (text.as_str(), true),
+            _ => return false
+        };
+
+        if self.in_header
+            && !bracketed
+            && let Some((name, value)) = text.split_once(':')
+            && let Some(directive) = header_directive(name.trim())
+            && !value.trim().is_empty()
+        {
+            let offset = text.len() - value.trim_start().len();
+            let value_span = Span::new(
+                span.line,
+                span.column + text[..offset].chars().count(),
+                value.trim().chars().count()
+            );
+            self.directive(
+                &Directive::new(directive, Some(value.trim())),
+                span,
+                value_span
+            );
+            return true;
+        }
+
+        let Some(heading) = Heading::parse(text.trim()).filter(|h| h.rest.is_empty()) else {
+            return false;
+        };
+        if !bracketed && !heading.colon {
+            return false;
+        }
+
+        self.flush_section();
+        self.in_header = false;
+        self.current_section = Some(SongSection {
+            section_type: ChordProParser::parse_section_type(heading.kind()),
+            label:        Some(text.trim().trim_end_matches(':').trim_end().to_string()),
+            lines:        Vec::new(),
+            recall:       None,
+            grid:         None
+        });
+        self.heading = Some(span);
+        true
+    }
+
+    fn directive(&mut self, directive: &Directive, line_span: Span, value_span: Span) {
+        let name = directive.normalized_name();
+        let value = directive.value.clone();
+
+        if let Some(kind) = ChordProParser::section_start(&name) {
+            self.start_section(&kind, value, line_span);
+            return;
+        }
+
+        if let Some(kind) = ChordProParser::section_end(&name) {
+            self.end_section(&kind, line_span);
+            return;
+        }
+
+        match name.as_str() {
+            "title" | "t" => self.song.title = value,
+            "subtitle" | "st" => self.song.subtitle = value,
+            "artist" | "a" => self.song.artist = value,
+            "composer" => self.song.composer = value,
+            "key" => self.song.key = value,
+            "tempo" => {
+                self.song.tempo = value.as_deref().and_then(|v| v.parse().ok());
+                if self.song.tempo.is_none() {
+                    self.diagnostics.push(Diagnostic::error(
+                        DiagnosticCode::InvalidTempo,
+                        value_span,
+                        format!("invalid tempo '{}'", value.unwrap_or_default())
+                    ));
+                }
+            }
+            "time" => self.song.time_signature = value,
+            "capo" => {
+                self.song.capo = value.as_deref().and_then(|v| v.parse().ok());
+                if self.song.capo.is_none() {
+                    self.diagnostics.push(Diagnostic::error(
+                        DiagnosticCode::InvalidCapo,
+                        value_span,
+                        format!("invalid capo '{}'", value.unwrap_or_default())
+                    ));
+                }
+            }
+            "chorus" => self.recall_chorus(value, line_span),
+            "define" | "chord" => match value.as_deref().and_then(ChordDefinition::parse) {
+                Some(definition) => self.song.definitions.push(definition),
+                None => self.diagnostics.push(Diagnostic::error(
+                    DiagnosticCode::InvalidDefinition,
+                    value_span,
+                    format!("invalid chord definition '{}'", value.unwrap_or_default())
+                ))
+            },
+            "c" | "comment" => {
+                if let Some(ref mut section) = self.current_section
+                    && let Some(text) = value
+                {
+                    section.lines.push(SongLine {
+                        text,
+                        chords: Vec::new()
+                    });
+                }
+            }
+            name if self.dialect == Dialect::Compat && name.starts_with("x_") => {}
+            name if UNSUPPORTED_DIRECTIVES.contains(&name) => {
+                self.diagnostics.push(Diagnostic::info(
+                    DiagnosticCode::UnsupportedDirective,
+                    line_span,
+                    format!("directive '{}' is ignored", name)
+                ));
+            }
+            name => {
+                self.diagnostics.push(Diagnostic::warning(
+                    DiagnosticCode::UnknownDirective,
+                    line_span,
+                    format!("unknown directive '{}'", name)
+                ));
+            }
+        }
+    }
+
+    /// Repeat the last chorus defined so far
+    fn recall_chorus(&mut self, label: Option<String>, span: Span) {
+        self.flush_section();
+
+        let chorus = self
+            .song
+            .sections
+            .iter()
+            .rposition(|s| s.section_type == SongSectionType::Chorus && s.recall.is_none());
+
+        match chorus {
+            Some(index) => self.song.sections.push(SongSection {
+                section_type: SongSectionType::Chorus,
+                label,
+                lines: Vec::new(),
+                recall: Some(index),
+                grid: None
+            }),
+            None => self.diagnostics.push(Diagnostic::warning(
+                DiagnosticCode::MissingChorus,
+                span,
+                "chorus recalled before any chorus is defined"
+            ))
+        }
+    }
+
+    fn start_section(&mut self, kind: &str, label: Option<String>, span: Span) {
+        self.flush_section();
+        self.in_header = false;
+
+        self.current_section = Some(SongSection {
+            section_type: ChordProParser::parse_section_type(kind),
+            label,
+            lines: Vec::new(),
+            recall: None,
+            grid: None
+        });
+        self.open_section = Some(OpenSection {
+            kind: kind.to_string(),
+            span
+        });
+    }
+
+    fn end_section(&mut self, kind: &str, span: Span) {
+        match self.open_section.take() {
+            Some(open) if open.kind == kind => {}
+            Some(open) => self.diagnostics.push(Diagnostic::warning(
+                DiagnosticCode::UnmatchedSectionEnd,
+                span,
+                format!("end of {} does not match open {} section", kind, open.kind)
+            )),
+            None => self.diagnostics.push(Diagnostic::warning(
+                DiagnosticCode::UnmatchedSectionEnd,
+                span,
+                format!("end of {} without matching start", kind)
+            ))
+        }
+
+        self.flush_section();
+    }
+
+    fn finish(mut self) -> (ParsedSong, Vec<Diagnostic>) {
+        if let Some(open) = self.open_section.take() {
+            self.diagnostics.push(Diagnostic::warning(
+                DiagnosticCode::UnclosedSection,
+                open.span,
+                format!("{} section is never closed", open.kind)
+            ));
+        }
+
+        self.flush_section();
+
+        (self.song, self.diagnostics)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Severity;
 
     #[test]
     fn test_parse_simple_song() {
@@ -544,15 +968,394 @@ Second line
             ChordProParser::parse_section_type("coda"),
             SongSectionType::Ending
         );
+        assert_eq!(
+            ChordProParser::parse_section_type("tab"),
+            SongSectionType::Tab
+        );
+        assert_eq!(
+            ChordProParser::parse_section_type("grid"),
+            SongSectionType::Grid
+        );
+        assert_eq!(
+            ChordProParser::parse_section_type("abc"),
+            SongSectionType::Abc
+        );
+        assert_eq!(
+            ChordProParser::parse_section_type("ly"),
+            SongSectionType::Lilypond
+        );
+        assert_eq!(
+            ChordProParser::parse_section_type("textblock"),
+            SongSectionType::Textblock
+        );
         assert_eq!(
             ChordProParser::parse_section_type("unknown"),
             SongSectionType::Other
         );
     }
 
+    #[test]
+    fn test_strip_escaped_lyrics() {
+        let content = "\\[not a chord] [C]and \\{x}";
+        assert_eq!(
+            ChordProParser::strip_chords(content),
+            "[not a chord] and {x}"
+        );
+        assert_eq!(
+            ChordProParser::extract_first_line(content),
+            "[not a chord] and {x}"
+        );
+    }
+
     #[test]
     fn test_extract_first_line_empty() {
         let content = "{title: Test}\n{key: Am}";
         assert_eq!(ChordProParser::extract_first_line(content), "");
     }
+
+    #[test]
+    fn test_parse_with_diagnostics_clean_song() {
+        let content = "{title: Test}\n{start_of_verse}\n[Am]Hello\n{end_of_verse}";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(song.title, Some("Test".to_string()));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_unknown_directive() {
+        let content = "{title: Test}\n  {foo: bar}";
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnknownDirective);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].span, Span::new(2, 3, 10));
+    }
+
+    #[test]
+    fn test_diagnostic_unsupported_directive() {
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics("{textfont: Arial}");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnsupportedDirective);
+        assert_eq!(diagnostics[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_diagnostic_unmatched_section_end() {
+        let content = "[Am]Hello\n{end_of_chorus}";
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnmatchedSectionEnd);
+        assert_eq!(diagnostics[0].span.line, 2);
+    }
+
+    #[test]
+    fn test_diagnostic_mismatched_section_end() {
+        let content = "{start_of_verse}\nHello\n{end_of_chorus}";
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnmatchedSectionEnd);
+    }
+
+    #[test]
+    fn test_diagnostic_unclosed_section() {
+        let content = "{start_of_chorus}\nHello";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(song.sections.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnclosedSection);
+        assert_eq!(diagnostics[0].span, Span::new(1, 1, 17));
+    }
+
+    #[test]
+    fn test_diagnostic_invalid_chord() {
+        let content = "Hello [Xyz]world";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(song.sections[0].lines[0].text, "Hello world");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidChord);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].span, Span::new(1, 7, 5));
+    }
+
+    #[test]
+    fn test_diagnostic_column_counts_characters() {
+        let content = "Привет [Xyz]мир";
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(diagnostics[0].span, Span::new(1, 8, 5));
+    }
+
+    #[test]
+    fn test_diagnostic_annotation_is_not_invalid_chord() {
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics("[*Riff]Hello");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_unclosed_chord() {
+        let content = "[Am]Hello [G";
+        let (_, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnclosedChord);
+        assert_eq!(diagnostics[0].span, Span::new(1, 11, 2));
+    }
+
+    #[test]
+    fn test_diagnostic_invalid_tempo_and_capo() {
+        let content = "{tempo: fast}\n{capo:  abc }";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert!(song.tempo.is_none());
+        assert!(song.capo.is_none());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidTempo);
+        assert_eq!(diagnostics[0].span, Span::new(1, 9, 4));
+        assert_eq!(diagnostics[1].code, DiagnosticCode::InvalidCapo);
+        assert_eq!(diagnostics[1].span, Span::new(2, 9, 3));
+    }
+
+    #[test]
+    fn test_parse_short_section_directives() {
+        let content = "{sov: 1}\nVerse line\n{eov}\n{soc}\nChorus line\n{eoc}";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.sections.len(), 2);
+        assert_eq!(song.sections[0].section_type, SongSectionType::Verse);
+        assert_eq!(song.sections[0].label, Some("1".to_string()));
+        assert_eq!(song.sections[1].section_type, SongSectionType::Chorus);
+    }
+
+    #[test]
+    fn test_parse_custom_section_directive() {
+        let content = "{start_of_intro}\n[G]\n{end_of_intro}";
+        let song = ChordProParser::parse(content);
+        assert_eq!(song.sections[0].section_type, SongSectionType::Intro);
+    }
+
+    #[test]
+    fn test_parse_skips_comment_lines() {
+        let content = "# imported from archive\n[Am]Hello";
+        let song = ChordProParser::parse(content);
+        assert_eq!(song.sections.len(), 1);
+        assert_eq!(song.sections[0].lines.len(), 1);
+        assert_eq!(song.sections[0].lines[0].text, "Hello");
+    }
+
+    #[test]
+    fn test_parse_tree_matches_parse() {
+        let content = "{title: Test}\n{soc}\n[Am]Hello [G]world\n{eoc}\n";
+        let tree = SyntaxTree::parse(content);
+        let (song, _) = ChordProParser::parse_tree(&tree);
+        assert_eq!(tree.to_string(), content);
+        assert_eq!(song.title, Some("Test".to_string()));
+        assert_eq!(song.sections[0].lines[0].chords.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_define_directives() {
+        let content = "{define: Asus2 base-fret 1 frets x 0 2 2 0 0}\n{chord: Am}\n[Asus2]Hi";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.definitions.len(), 2);
+        assert_eq!(song.definitions[0].name, "Asus2");
+        assert_eq!(song.definitions[0].frets.len(), 6);
+        assert_eq!(song.definitions[1].name, "Am");
+    }
+
+    #[test]
+    fn test_parse_invalid_define() {
+        let content = "{define: Am frets a b c}";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert!(song.definitions.is_empty());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidDefinition);
+        assert_eq!(diagnostics[0].span, Span::new(1, 10, 14));
+    }
+
+    #[test]
+    fn test_parse_chorus_recall() {
+        let content = r#"
+{start_of_verse}
+Verse one
+{end_of_verse}
+{start_of_chorus}
+Chorus line
+{end_of_chorus}
+{start_of_verse}
+Verse two
+{end_of_verse}
+{chorus: Repeat}
+Loose line
+"#;
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.sections.len(), 5);
+
+        let recall = &song.sections[3];
+        assert_eq!(recall.section_type, SongSectionType::Chorus);
+        assert_eq!(recall.label, Some("Repeat".to_string()));
+        assert_eq!(recall.recall, Some(1));
+        assert!(recall.lines.is_empty());
+
+        assert_eq!(song.sections[4].section_type, SongSectionType::Verse);
+        assert_eq!(song.sections[4].lines[0].text, "Loose line");
+    }
+
+    #[test]
+    fn test_parse_chorus_recall_without_chorus() {
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics("Line\n{chorus}");
+        assert_eq!(song.sections.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::MissingChorus);
+    }
+
+    #[test]
+    fn test_parse_tab_verbatim() {
+        let content = "{start_of_tab: Intro}\ne|--[0]--3--|\n  B|-----1---|  \n\n# not a comment\n{c: nor a directive}\n{end_of_tab}";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.sections.len(), 1);
+
+        let tab = &song.sections[0];
+        assert_eq!(tab.section_type, SongSectionType::Tab);
+        assert_eq!(tab.label, Some("Intro".to_string()));
+        let lines: Vec<_> = tab.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "e|--[0]--3--|",
+                "  B|-----1---|  ",
+                "",
+                "# not a comment",
+                "{c: nor a directive}"
+            ]
+        );
+        assert!(tab.lines.iter().all(|l| l.chords.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_verbatim_short_directives() {
+        let content = "{sot}\n[x]\n{eot}\n{start_of_abc}\nX:1\n{end_of_abc}\n{start_of_ly}\n\\relative c'\n{end_of_ly}\n{start_of_textblock}\n  Free text\n{end_of_textblock}";
+        let song = ChordProParser::parse(content);
+        let types: Vec<_> = song.sections.iter().map(|s| s.section_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                SongSectionType::Tab,
+                SongSectionType::Abc,
+                SongSectionType::Lilypond,
+                SongSectionType::Textblock
+            ]
+        );
+        assert_eq!(song.sections[0].lines[0].text, "[x]");
+        assert_eq!(song.sections[3].lines[0].text, "  Free text");
+    }
+
+    #[test]
+    fn test_parse_grid_section() {
+        let content =
+            "{start_of_grid: 1+4x2+4}\nA || G . . . | C . D . |\n| Em . . . | % |\n{end_of_grid}";
+        let song = ChordProParser::parse(content);
+        let section = &song.sections[0];
+        assert_eq!(section.section_type, SongSectionType::Grid);
+        assert_eq!(section.lines[0].text, "A || G . . . | C . D . |");
+
+        let grid = section.grid.as_ref().unwrap();
+        assert_eq!(grid.rows.len(), 2);
+        assert_eq!(grid.rows[0].margin, Some("A".to_string()));
+        assert_eq!(grid.rows[0].bars.len(), 2);
+        assert_eq!(grid.rows[1].bars[1].cells, vec![crate::GridCell::RepeatBar]);
+    }
+
+    #[test]
+    fn test_parse_unclosed_tab_swallows_rest() {
+        let content = "{sot}\n[Am]Not lyrics";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+        assert_eq!(song.sections[0].lines[0].text, "[Am]Not lyrics");
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnclosedSection);
+    }
+
+    #[test]
+    fn test_parse_compat_headers_and_headings() {
+        let content = "Title: Amazing Grace\nArtist: John Newton\nKey: G\nCCLI: 22025\n\n\
+                       Verse 1:\n[G]Amazing [C]grace\n\nChorus:\n[D]How sweet\n\n\
+                       [Verse 2]\nTwas grace\nKey: not a header here";
+        let (song, diagnostics) =
+            ChordProParser::parse_dialect_with_diagnostics(content, Dialect::Compat);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.title.as_deref(), Some("Amazing Grace"));
+        assert_eq!(song.artist.as_deref(), Some("John Newton"));
+        assert_eq!(song.key.as_deref(), Some("G"));
+        assert_eq!(song.sections.len(), 3);
+        assert_eq!(song.sections[0].section_type, SongSectionType::Verse);
+        assert_eq!(song.sections[0].label.as_deref(), Some("Verse 1"));
+        assert_eq!(song.sections[0].lines[0].text, "Amazing grace");
+        assert_eq!(song.sections[1].section_type, SongSectionType::Chorus);
+        assert_eq!(song.sections[2].label.as_deref(), Some("Verse 2"));
+        assert_eq!(song.sections[2].lines[1].text, "Key: not a header here");
+    }
+
+    #[test]
+    fn test_parse_compat_matches_standard() {
+        let compat = "Title: Song\nTempo: 90\n{x_onsong_flow: V1 C}\n{textfont: Arial}\n\
+                      Chorus:\n[*Riff][Am]Glory\n{sot}\ne|--0--|\n{eot}";
+        let standard = "{title: Song}\n{tempo: 90}\n{textfont: Arial}\n\
+                        {start_of_chorus: Chorus}\n[*Riff][Am]Glory\n{end_of_chorus}\n\
+                        {sot}\ne|--0--|\n{eot}";
+
+        let (song, diagnostics) =
+            ChordProParser::parse_dialect_with_diagnostics(compat, Dialect::Compat);
+        assert_eq!(song, ChordProParser::parse(standard));
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.code == DiagnosticCode::UnsupportedDirective)
+        );
+    }
+
+    #[test]
+    fn test_parse_compat_russian_headings() {
+        let content = "Куплет 1:\nТекст\nПре-припев:\nЕщё\nПрипев:\nСлава";
+        let song = ChordProParser::parse_dialect(content, Dialect::Compat);
+
+        let types: Vec<SongSectionType> = song.sections.iter().map(|s| s.section_type).collect();
+        assert_eq!(
+            types,
+            [
+                SongSectionType::Verse,
+                SongSectionType::PreChorus,
+                SongSectionType::Chorus
+            ]
+        );
+        assert_eq!(song.sections[2].label.as_deref(), Some("Припев"));
+    }
+
+    #[test]
+    fn test_parse_compat_repeated_chorus_heading() {
+        let content = "Chorus:\n[C]Glory\n\nVerse 2:\nAmen\n\nChorus:\n\nVerse 3:\nAgain\nChorus:";
+        let (song, diagnostics) =
+            ChordProParser::parse_dialect_with_diagnostics(content, Dialect::Compat);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.sections.len(), 5);
+        assert_eq!(song.sections[2].recall, Some(0));
+        assert_eq!(song.sections[2].label.as_deref(), Some("Chorus"));
+        assert_eq!(song.sections[3].lines[0].text, "Again");
+        assert_eq!(song.sections[4].recall, Some(0));
+
+        let (song, diagnostics) =
+            ChordProParser::parse_dialect_with_diagnostics("Chorus:\n", Dialect::Compat);
+        assert!(song.sections.is_empty());
+        assert_eq!(diagnostics[0].code, DiagnosticCode::MissingChorus);
+    }
+
+    #[test]
+    fn test_parse_standard_ignores_compat_syntax() {
+        let content = "Title: Song\nChorus:\n{x_custom}";
+        let (song, diagnostics) = ChordProParser::parse_with_diagnostics(content);
+
+        assert_eq!(song.title, None);
+        assert_eq!(song.sections[0].lines[0].text, "Title: Song");
+        assert_eq!(song.sections[0].lines[1].text, "Chorus:");
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnknownDirective);
+    }
 }
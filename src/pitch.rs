@@ -0,0 +1,296 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Spelled notes and intervals
+//!
+//! [`Note`] is a pitch class and cannot tell `A#` from `Bb`. [`NoteName`]
+//! keeps the letter, so chord tones are spelled the way they are written:
+//! the third of `Eb` is `G`, the minor third of `F#` is `A`, not `Bb`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Note, naming::latin_lookalike};
+
+/// Natural note letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B
+}
+
+impl Letter {
+    pub(crate) const ALL: [Self; 7] = [
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::A,
+        Self::B
+    ];
+
+    /// Position in the C major scale (C = 0)
+    pub fn index(self) -> u8 {
+        self as u8
+    }
+
+    /// Semitone of the natural note (C = 0)
+    pub fn semitone(self) -> u8 {
+        [0, 2, 4, 5, 7, 9, 11][self.index() as usize]
+    }
+
+    /// Letter a number of scale steps above
+    pub fn step(self, steps: u8) -> Self {
+        Self::ALL[(self.index() + steps) as usize % 7]
+    }
+
+    /// Letter from an English note name, `H` is B
+    pub(crate) fn from_char(c: char) -> Option<Self> {
+        Some(match latin_lookalike(c).to_ascii_uppercase() {
+            'C' => Self::C,
+            'D' => Self::D,
+            'E' => Self::E,
+            'F' => Self::F,
+            'G' => Self::G,
+            'A' => Self::A,
+            'B' | 'H' => Self::B,
+            _ => return None
+        })
+    }
+}
+
+/// Note with its spelling (letter and accidentals)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct NoteName {
+    pub letter: Letter,
+    /// Semitones from the natural letter: -1 = flat, 1 = sharp, 2 = double
+    /// sharp
+    pub shift:  i8
+}
+
+impl NoteName {
+    /// Parse note like `C`, `F#`, `Bb`, `Ebb`, `G♯`
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut chars = s.trim().chars();
+        let letter = Letter::from_char(chars.next()?)?;
+
+        let mut shift = 0i8;
+        for c in chars {
+            shift += match c {
+                '#' | '♯' => 1,
+                'b' | '♭' => -1,
+                _ => return None
+            };
+            if shift.abs() > 2 {
+                return None;
+            }
+        }
+
+        Some(Self {
+            letter,
+            shift
+        })
+    }
+
+    /// Pitch class of the note
+    pub fn note(self) -> Note {
+        let semitone = self.letter.semitone() as i32 + self.shift as i32;
+        Note::from_semitone(semitone.rem_euclid(12) as u8)
+    }
+
+    /// Note an interval above, spelled by interval degree
+    ///
+    /// The third above `Eb` is `G`, the minor third above `F#` is `A`.
+    /// The shift is not limited, so exotic spellings like `Fbbb` can come out;
+    /// see [`NoteName::is_practical`].
+    pub fn above(self, interval: Interval) -> Self {
+        let letter = self.letter.step((interval.degree - 1) % 7);
+        Self::spelled_as(self.note().transpose(interval.semitones as i32), letter)
+    }
+
+    /// Spell a pitch class with the given letter (`Bb` as `A#` gives `A#`)
+    pub fn spelled_as(note: Note, letter: Letter) -> Self {
+        let mut shift = (note.to_semitone() as i8 - letter.semitone() as i8).rem_euclid(12);
+        if shift > 6 {
+            shift -= 12;
+        }
+
+        Self {
+            letter,
+            shift
+        }
+    }
+
+    /// At most a double accidental
+    pub fn is_practical(self) -> bool {
+        self.shift.abs() <= 2
+    }
+}
+
+impl fmt::Display for NoteName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.letter)?;
+        let accidental = if self.shift > 0 { "#" } else { "b" };
+        for _ in 0..self.shift.unsigned_abs() {
+            f.write_str(accidental)?;
+        }
+        Ok(())
+    }
+}
+
+/// Interval above the chord root, like `b3` or `#11`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Interval {
+    /// Scale degree, 1-based (9 is a compound second)
+    pub degree:    u8,
+    /// Size in semitones
+    pub semitones: u8
+}
+
+impl Interval {
+    pub const ROOT: Self = Self::new(1, 0);
+
+    pub const fn new(degree: u8, semitones: u8) -> Self {
+        Self {
+            degree,
+            semitones
+        }
+    }
+
+    /// Interval of a major scale degree (1, 2, 3, 4, 5, 6, 7, 9, 11, 13)
+    pub const fn natural(degree: u8) -> Self {
+        let octave = if degree > 7 { 12 } else { 0 };
+        let semitones = match (degree - 1) % 7 {
+            0 => 0,
+            1 => 2,
+            2 => 4,
+            3 => 5,
+            4 => 7,
+            5 => 9,
+            _ => 11
+        };
+        Self::new(degree, semitones + octave)
+    }
+
+    /// Interval raised or lowered by semitones
+    pub const fn altered(self, shift: i8) -> Self {
+        Self::new(self.degree, (self.semitones as i8 + shift) as u8)
+    }
+
+    /// Semitones from the natural degree: -1 for `b3`, 1 for `#11`
+    pub fn shift(self) -> i8 {
+        self.semitones as i8 - Self::natural(self.degree).semitones as i8
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shift = self.shift();
+        let accidental = if shift > 0 { "#" } else { "b" };
+        for _ in 0..shift.unsigned_abs() {
+            f.write_str(accidental)?;
+        }
+        write!(f, "{}", self.degree)
+    }
+}
+
+/// Spelled chord tone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordTone {
+    pub note:     NoteName,
+    pub interval: Interval
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(s: &str) -> NoteName {
+        NoteName::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_note_name() {
+        assert_eq!(name("C").note(), Note::C);
+        assert_eq!(name("F#").note(), Note::FSharp);
+        assert_eq!(name("Bb").note(), Note::ASharp);
+        assert_eq!(name("Cb").note(), Note::B);
+        assert_eq!(name("B#").note(), Note::C);
+        assert_eq!(name("Ebb").note(), Note::D);
+        assert_eq!(name("G♯").shift, 1);
+        assert_eq!(name("H").note(), Note::B);
+        assert_eq!(name("Hb").note(), Note::ASharp);
+        assert_eq!(name("С#").to_string(), "C#");
+        assert!(NoteName::parse("X").is_none());
+        assert!(NoteName::parse("Cm").is_none());
+        assert!(NoteName::parse("C###").is_none());
+    }
+
+    #[test]
+    fn test_display_note_name() {
+        for s in ["C", "F#", "Bb", "Ebb", "C##"] {
+            assert_eq!(name(s).to_string(), s);
+        }
+        assert_eq!(name("G♭").to_string(), "Gb");
+    }
+
+    #[test]
+    fn test_above_spells_by_degree() {
+        let minor_third = Interval::natural(3).altered(-1);
+        assert_eq!(name("F#").above(minor_third).to_string(), "A");
+        assert_eq!(name("Eb").above(Interval::natural(3)).to_string(), "G");
+        assert_eq!(name("Eb").above(minor_third).to_string(), "Gb");
+        assert_eq!(name("B").above(Interval::natural(5)).to_string(), "F#");
+        assert_eq!(name("G#").above(Interval::natural(3)).to_string(), "B#");
+        assert_eq!(name("D").above(Interval::natural(9)).to_string(), "E");
+        assert_eq!(
+            name("C")
+                .above(Interval::natural(7).altered(-2))
+                .to_string(),
+            "Bbb"
+        );
+    }
+
+    #[test]
+    fn test_interval_display() {
+        assert_eq!(Interval::ROOT.to_string(), "1");
+        assert_eq!(Interval::natural(3).altered(-1).to_string(), "b3");
+        assert_eq!(Interval::natural(5).to_string(), "5");
+        assert_eq!(Interval::natural(7).altered(-1).to_string(), "b7");
+        assert_eq!(Interval::natural(7).altered(-2).to_string(), "bb7");
+        assert_eq!(Interval::natural(9).altered(1).to_string(), "#9");
+        assert_eq!(Interval::natural(13).semitones, 21);
+    }
+
+    #[test]
+    fn test_spelled_as() {
+        assert_eq!(
+            NoteName::spelled_as(Note::ASharp, Letter::B).to_string(),
+            "Bb"
+        );
+        assert_eq!(
+            NoteName::spelled_as(Note::ASharp, Letter::A).to_string(),
+            "A#"
+        );
+        assert_eq!(NoteName::spelled_as(Note::C, Letter::B).to_string(), "B#");
+    }
+
+    #[test]
+    fn test_practical_spelling() {
+        assert!(name("Ebb").is_practical());
+        let fb = name("Fb");
+        let dim_seventh = Interval::natural(7).altered(-2);
+        assert!(!fb.above(dim_seventh).is_practical());
+    }
+}
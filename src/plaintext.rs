@@ -0,0 +1,684 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! "Chords over lyrics" plain text
+//!
+//! Songs copied from websites or word processors keep chords on their own
+//! line above the lyrics. On import each line is classified as blank, a
+//! section heading, a chord line or a lyric line; chord lines are merged
+//! into the lyric line below at the same character columns, and headings
+//! like `Куплет 1:` or `Chorus` open ChordPro sections. Export does the
+//! reverse for fixed-width fonts, spacing out lyrics where chords collide.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::{
+    Chord, ChordProParser, Diagnostic, DiagnosticCode, ParsedSong, SongLine, SongSection, Span,
+    heading::Heading
+};
+
+/// Tablature line like `e|---0---3---|`
+static TAB_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Ga-g]?\|[-0-9|hpbrsx/\\~()\s]*$").unwrap());
+
+/// Tab stop width used to expand tabs before aligning chords
+const TAB_WIDTH: usize = 8;
+
+/// Converter from plain "chords over lyrics" text to ChordPro
+pub struct PlainTextImporter;
+
+impl PlainTextImporter {
+    /// Convert plain text to ChordPro, reporting lines that could not be
+    /// classified
+    ///
+    /// Unclassified lines are kept as lyrics.
+    pub fn import(text: &str) -> (String, Vec<Diagnostic>) {
+        let lines: Vec<(usize, PlainLine)> = text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                classify(&expand(line))
+                    .into_iter()
+                    .map(move |line| (index + 1, line))
+            })
+            .collect();
+
+        let mut out = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut section: Option<&str> = None;
+        let mut iter = lines.iter().peekable();
+
+        while let Some((line_no, line)) = iter.next() {
+            match line {
+                PlainLine::Blank => out.push(String::new()),
+                PlainLine::Heading {
+                    kind,
+                    label
+                } => {
+                    close_section(&mut out, section);
+                    out.push(format!("{{start_of_{}: {}}}", kind, label));
+                    section = Some(kind);
+                }
+                PlainLine::Chords(tokens) => match iter.peek() {
+                    Some((_, PlainLine::Lyrics(lyrics))) => {
+                        out.push(merge(tokens, lyrics));
+                        iter.next();
+                    }
+                    _ => out.push(chord_only(tokens))
+                },
+                PlainLine::Lyrics(text) => out.push(text.trim_end().to_string()),
+                PlainLine::Unclassified {
+                    text,
+                    reason
+                } => {
+                    diagnostics.push(Diagnostic::warning(
+                        DiagnosticCode::UnclassifiedLine,
+                        Span::new(*line_no, 1, text.chars().count()),
+                        format!("{} kept as lyrics", reason)
+                    ));
+                    out.push(text.trim_end().to_string());
+                }
+            }
+        }
+        close_section(&mut out, section);
+
+        while out.last().is_some_and(String::is_empty) {
+            out.pop();
+        }
+        while out.first().is_some_and(String::is_empty) {
+            out.remove(0);
+        }
+
+        let mut content = out.join("\n");
+        content.push('\n');
+        (content, diagnostics)
+    }
+
+    /// Import plain text straight into a parsed song
+    ///
+    /// Diagnostics refer to lines of the plain text.
+    pub fn import_song(text: &str) -> (ParsedSong, Vec<Diagnostic>) {
+        let (content, diagnostics) = Self::import(text);
+        (ChordProParser::parse(&content), diagnostics)
+    }
+}
+
+/// Language of section headers and metadata labels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextLanguage {
+    /// `Куплет 1:`, `Тональность: G`
+    #[default]
+    Russian,
+    /// `Verse 1:`, `Key: G`
+    English
+}
+
+/// Plain text writer options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlainTextOptions {
+    pub language:        TextLanguage,
+    /// Emit `Куплет 1:` style headers before sections
+    pub section_headers: bool,
+    /// Emit title, artist, key and other metadata lines
+    pub metadata:        bool
+}
+
+impl Default for PlainTextOptions {
+    fn default() -> Self {
+        Self {
+            language:        TextLanguage::Russian,
+            section_headers: true,
+            metadata:        true
+        }
+    }
+}
+
+/// Writer of fixed-width "chords over lyrics" text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainTextWriter {
+    options: PlainTextOptions
+}
+
+impl PlainTextWriter {
+    pub fn new(options: PlainTextOptions) -> Self {
+        Self {
+            options
+        }
+    }
+
+    /// Render song as text with chords above the lyrics
+    ///
+    /// Recalled choruses are written out in full.
+    pub fn write(&self, song: &ParsedSong) -> String {
+        let mut blocks = Vec::new();
+
+        if self.options.metadata {
+            let metadata = self.metadata(song);
+            if !metadata.is_empty() {
+                blocks.push(metadata);
+            }
+        }
+
+        for section in song.performance_order() {
+            blocks.push(self.section(section));
+        }
+
+        blocks.retain(|block| !block.is_empty());
+        blocks.join("\n")
+    }
+
+    fn metadata(&self, song: &ParsedSong) -> String {
+        let mut out = String::new();
+        for value in [&song.title, &song.subtitle, &song.artist]
+            .into_iter()
+            .flatten()
+        {
+            out.push_str(&format!("{}\n", value));
+        }
+
+        let labels = match self.options.language {
+            TextLanguage::Russian => ["Тональность", "Каподастр", "Темп", "Размер"],
+            TextLanguage::English => ["Key", "Capo", "Tempo", "Time"]
+        };
+        let details: Vec<String> = [
+            song.key.clone(),
+            song.capo.map(|c| c.to_string()),
+            song.tempo.map(|t| t.to_string()),
+            song.time_signature.clone()
+        ]
+        .into_iter()
+        .zip(labels)
+        .filter_map(|(value, label)| Some(format!("{}: {}", label, value?)))
+        .collect();
+
+        if !details.is_empty() {
+            out.push_str(&format!("{}\n", details.join(", ")));
+        }
+        out
+    }
+
+    fn section(&self, section: &SongSection) -> String {
+        let mut out = String::new();
+
+        if self.options.section_headers
+            && let Some(header) = self.header(section)
+        {
+            out.push_str(&format!("{}:\n", header));
+        }
+
+        for line in &section.lines {
+            if section.section_type.is_verbatim() {
+                out.push_str(&format!("{}\n", line.text));
+            } else {
+                out.push_str(&write_line(line));
+            }
+        }
+        out
+    }
+
+    /// Section name with a numeric label appended, or a custom label as is
+    fn header(&self, section: &SongSection) -> Option<String> {
+        let name = match self.options.language {
+            TextLanguage::Russian => section.section_type.name_ru(),
+            TextLanguage::English => section.section_type.name_en()
+        };
+
+        match section.label.as_deref().map(str::trim) {
+            Some(label) if !label.is_empty() && label.chars().all(|c| c.is_ascii_digit()) => {
+                Some(format!("{} {}", name, label).trim().to_string())
+            }
+            Some(label) if !label.is_empty() => Some(label.to_string()),
+            _ => (!name.is_empty()).then(|| name.to_string())
+        }
+    }
+}
+
+/// Chord line above the lyric line; lyrics are spaced out where chords
+/// would otherwise touch, with `-` inside words
+fn write_line(line: &SongLine) -> String {
+    let text: Vec<char> = line.text.chars().collect();
+    let mut chords: Vec<_> = line.chords.iter().collect();
+    chords.sort_by_key(|chord| chord.position);
+
+    let mut chord_line = String::new();
+    let mut lyric_line = String::new();
+    let mut chord_width = 0;
+    let mut lyric_width = 0;
+    let mut position = 0;
+
+    for positioned in chords {
+        let target = positioned.position.min(text.len());
+        for &c in &text[position..target] {
+            lyric_line.push(c);
+            lyric_width += 1;
+        }
+        position = target;
+
+        let column = if chord_width == 0 { 0 } else { chord_width + 1 };
+        if lyric_width < column {
+            let in_word = position > 0
+                && position < text.len()
+                && text[position - 1].is_alphanumeric()
+                && text[position].is_alphanumeric();
+            let fill = if in_word { '-' } else { ' ' };
+            lyric_line.extend(std::iter::repeat_n(fill, column - lyric_width));
+            lyric_width = column;
+        }
+
+        let chord = positioned.chord.to_string();
+        chord_line.extend(std::iter::repeat_n(' ', lyric_width - chord_width));
+        chord_line.push_str(&chord);
+        chord_width = lyric_width + chord.chars().count();
+    }
+    lyric_line.extend(&text[position..]);
+
+    let mut out = String::new();
+    for line in [chord_line.trim_end(), lyric_line.trim_end()] {
+        if !line.is_empty() {
+            out.push_str(&format!("{}\n", line));
+        }
+    }
+    if out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Classified plain-text line
+#[derive(Debug)]
+enum PlainLine {
+    Blank,
+    Heading {
+        kind:  &'static str,
+        label: String
+    },
+    Chords(Vec<(usize, ChordToken)>),
+    Lyrics(String),
+    Unclassified {
+        text:   String,
+        reason: &'static str
+    }
+}
+
+/// Word of a chord line with its character column
+#[derive(Debug)]
+enum ChordToken {
+    Chord(Chord),
+    /// Bar line, repeat count or other marking kept on chord-only lines
+    Mark(String)
+}
+
+/// Replace tabs and non-breaking spaces so columns match what was seen
+fn expand(line: &str) -> String {
+    let mut out = String::new();
+    let mut column = 0;
+    for c in line.trim_end_matches('\r').chars() {
+        match c {
+            '\t' => {
+                let next = (column / TAB_WIDTH + 1) * TAB_WIDTH;
+                out.extend(std::iter::repeat_n(' ', next - column));
+                column = next;
+            }
+            '\u{a0}' => {
+                out.push(' ');
+                column += 1;
+            }
+            c => {
+                out.push(c);
+                column += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Classify a line; a heading followed by chords or text yields two lines
+fn classify(line: &str) -> Vec<PlainLine> {
+    if line.trim().is_empty() {
+        return vec![PlainLine::Blank];
+    }
+
+    if let Some(heading) = Heading::parse(line.trim()) {
+        let rest_line = (!heading.rest.is_empty()).then(|| classify_body(heading.rest));
+
+        if heading.colon
+            || rest_line
+                .as_ref()
+                .is_none_or(|l| matches!(l, PlainLine::Chords(_)))
+        {
+            let mut lines = vec![PlainLine::Heading {
+                kind:  heading.kind(),
+                label: heading.label()
+            }];
+            lines.extend(rest_line);
+            return lines;
+        }
+    }
+
+    vec![classify_body(line)]
+}
+
+/// Classify a line that is not a heading
+fn classify_body(line: &str) -> PlainLine {
+    let tokens = tokens(line);
+    let chords = tokens
+        .iter()
+        .filter(|(_, token)| matches!(token, Some(ChordToken::Chord(_))))
+        .count();
+    let words = tokens.iter().filter(|(_, token)| token.is_none()).count();
+
+    if chords > 0 && words == 0 {
+        return PlainLine::Chords(
+            tokens
+                .into_iter()
+                .filter_map(|(column, token)| Some((column, token?)))
+                .collect()
+        );
+    }
+
+    let reason = if TAB_RE.is_match(line.trim()) {
+        Some("tablature line")
+    } else if chords > words {
+        Some("line mixes chords and words")
+    } else if !line.chars().any(char::is_alphabetic) {
+        Some("line has no chords or words")
+    } else {
+        None
+    };
+
+    match reason {
+        Some(reason) => PlainLine::Unclassified {
+            text: line.to_string(),
+            reason
+        },
+        None => PlainLine::Lyrics(line.to_string())
+    }
+}
+
+/// Whitespace-separated words with their character columns; `None` for
+/// words that are neither chords nor markings
+fn tokens(line: &str) -> Vec<(usize, Option<ChordToken>)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let chars: Vec<char> = line.chars().collect();
+
+    for (column, &c) in chars.iter().chain(std::iter::once(&' ')).enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(column),
+            (true, Some(from)) => {
+                let word: String = chars[from..column].iter().collect();
+                tokens.push((from, chord_token(&word)));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+fn chord_token(word: &str) -> Option<ChordToken> {
+    let inner = word
+        .strip_prefix('(')
+        .and_then(|w| w.strip_suffix(')'))
+        .unwrap_or(word);
+
+    // Cyrillic look-alikes like `А С Е` are lyrics here, not chords
+    let cyrillic = inner.chars().any(|c| matches!(c, '\u{400}'..='\u{4ff}'));
+    if let Some(chord) = Chord::parse(inner).filter(|chord| !cyrillic && chord.is_valid()) {
+        return Some(ChordToken::Chord(chord));
+    }
+
+    let lower = inner.to_lowercase();
+    let repeat = lower
+        .strip_prefix(['x', 'х', '×'])
+        .or_else(|| lower.strip_suffix(['x', 'х', '×']))
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    let mark = repeat
+        || matches!(lower.as_str(), "n.c." | "nc" | "n.c" | "%" | "...")
+        || lower
+            .chars()
+            .all(|c| matches!(c, '|' | ':' | '-' | '–' | '/' | '.'));
+
+    mark.then(|| ChordToken::Mark(word.to_string()))
+}
+
+/// Put chords into the lyric line at their columns
+fn merge(tokens: &[(usize, ChordToken)], lyrics: &str) -> String {
+    let chars: Vec<char> = lyrics.trim_end().chars().collect();
+    let mut out = String::new();
+    let mut position = 0;
+
+    for (column, token) in tokens {
+        let ChordToken::Chord(chord) = token else {
+            continue;
+        };
+        while position < *column && position < chars.len() {
+            out.push(chars[position]);
+            position += 1;
+        }
+        if position < *column && !out.is_empty() && !out.ends_with(' ') {
+            out.push(' ');
+        }
+        out.push_str(&format!("[{}]", chord));
+    }
+    out.extend(chars.iter().skip(position));
+    out
+}
+
+/// Chord line without lyrics below, like an intro or an instrumental
+fn chord_only(tokens: &[(usize, ChordToken)]) -> String {
+    tokens
+        .iter()
+        .map(|(_, token)| match token {
+            ChordToken::Chord(chord) => format!("[{}]", chord),
+            ChordToken::Mark(mark) => mark.clone()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Close the open section, keeping trailing blank lines after it
+///
+/// A chorus heading without lines repeats the chorus and becomes `{chorus}`.
+fn close_section(out: &mut Vec<String>, section: Option<&str>) {
+    let Some(kind) = section else {
+        return;
+    };
+    let blanks = out.iter().rev().take_while(|line| line.is_empty()).count();
+    out.truncate(out.len() - blanks);
+    match out.last_mut() {
+        Some(start) if kind == "chorus" && start.starts_with("{start_of_chorus") => {
+            *start = start.replacen("{start_of_chorus", "{chorus", 1);
+        }
+        _ => out.push(format!("{{end_of_{}}}", kind))
+    }
+    out.extend(std::iter::repeat_n(String::new(), blanks));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SongSectionType;
+
+    #[test]
+    fn test_merge_chords_into_lyrics() {
+        let text = "Am        F\nСлава Тебе, Господь\nC    G\nHello world\n";
+        let (content, diagnostics) = PlainTextImporter::import(text);
+
+        assert_eq!(content, "[Am]Слава Тебе[F], Господь\n[C]Hello[G] world\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_chords_past_end_of_lyrics() {
+        let (content, _) = PlainTextImporter::import("C       G    D\nAmen\n");
+        assert_eq!(content, "[C]Amen [G] [D]\n");
+    }
+
+    #[test]
+    fn test_headings() {
+        let text =
+            "Куплет 1:\nG    D\nО Боже мой\n\nПрипев:\nC\nАллилуйя\n\n[Chorus]\nHallelujah\n";
+        let (content, _) = PlainTextImporter::import(text);
+
+        assert_eq!(
+            content,
+            "{start_of_verse: Куплет 1}\n[G]О Бож[D]е мой\n{end_of_verse}\n\n\
+             {start_of_chorus: Припев}\n[C]Аллилуйя\n{end_of_chorus}\n\n\
+             {start_of_chorus: Chorus}\nHallelujah\n{end_of_chorus}\n"
+        );
+    }
+
+    #[test]
+    fn test_chorus_heading_without_lines() {
+        let text = "Припев:\nСлава\n\nКуплет 2:\nТекст\n\nПрипев:\n";
+        let (content, _) = PlainTextImporter::import(text);
+        assert_eq!(
+            content,
+            "{start_of_chorus: Припев}\nСлава\n{end_of_chorus}\n\n\
+             {start_of_verse: Куплет 2}\nТекст\n{end_of_verse}\n\n{chorus: Припев}\n"
+        );
+
+        let (song, _) = PlainTextImporter::import_song(text);
+        assert_eq!(song.sections.len(), 3);
+        assert_eq!(song.sections[2].recall, Some(0));
+    }
+
+    #[test]
+    fn test_heading_with_chords() {
+        let (content, _) = PlainTextImporter::import("Вступление: Am | F | C | G x2\n");
+        assert_eq!(
+            content,
+            "{start_of_intro: Вступление}\n[Am] | [F] | [C] | [G] x2\n{end_of_intro}\n"
+        );
+    }
+
+    #[test]
+    fn test_lyrics_starting_with_heading_word() {
+        let (content, _) = PlainTextImporter::import("Chorus of angels sing\n");
+        assert_eq!(content, "Chorus of angels sing\n");
+    }
+
+    #[test]
+    fn test_chord_lines_without_lyrics() {
+        let (content, _) = PlainTextImporter::import("Em  C\nD   G\nТекст\n\nA\n");
+        assert_eq!(content, "[Em] [C]\n[D]Текс[G]т\n\n[A]\n");
+    }
+
+    #[test]
+    fn test_cyrillic_lyrics_are_not_chords() {
+        let (content, _) = PlainTextImporter::import("А я\nВ небе\n");
+        assert_eq!(content, "А я\nВ небе\n");
+
+        let (content, _) = PlainTextImporter::import("а с\nВ небе\n");
+        assert_eq!(content, "а с\nВ небе\n");
+
+        let (content, _) = PlainTextImporter::import("А С Е\nG\nВ небе\n");
+        assert_eq!(content, "А С Е\n[G]В небе\n");
+    }
+
+    #[test]
+    fn test_tabs_expand_to_columns() {
+        let (content, _) = PlainTextImporter::import("\tG\nHallelujah\n");
+        assert_eq!(content, "Halleluj[G]ah\n");
+    }
+
+    #[test]
+    fn test_unclassified_lines() {
+        let text = "e|---0---3---|\nAm C G Dm Lord\n";
+        let (content, diagnostics) = PlainTextImporter::import(text);
+
+        assert_eq!(content, "e|---0---3---|\nAm C G Dm Lord\n");
+        assert_eq!(diagnostics.len(), 2);
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.code == DiagnosticCode::UnclassifiedLine)
+        );
+        assert_eq!(diagnostics[0].span, Span::new(1, 1, 14));
+        assert_eq!(diagnostics[1].span.line, 2);
+    }
+
+    #[test]
+    fn test_import_song() {
+        let text = "Verse 1\nG         C\nAmazing grace\n\nChorus:\nD\nHow sweet\n";
+        let (song, diagnostics) = PlainTextImporter::import_song(text);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(song.sections.len(), 2);
+        assert_eq!(song.sections[0].section_type, SongSectionType::Verse);
+        assert_eq!(song.sections[0].label.as_deref(), Some("Verse 1"));
+        assert_eq!(song.sections[0].lines[0].text, "Amazing grace");
+        assert_eq!(song.sections[0].lines[0].chords.len(), 2);
+        assert_eq!(song.sections[0].lines[0].chords[1].position, 10);
+        assert_eq!(song.sections[1].section_type, SongSectionType::Chorus);
+    }
+
+    fn export(content: &str, options: PlainTextOptions) -> String {
+        PlainTextWriter::new(options).write(&ChordProParser::parse(content))
+    }
+
+    #[test]
+    fn test_export_chords_over_lyrics() {
+        let content = "{title: Amazing Grace}\n{key: G}\n{capo: 2}\n\n{start_of_verse: 1}\n\
+                       [G]Amazing [C]grace\n{end_of_verse}\n";
+
+        assert_eq!(
+            export(content, PlainTextOptions::default()),
+            "Amazing Grace\nТональность: G, Каподастр: 2\n\nКуплет 1:\nG       C\nAmazing grace\n"
+        );
+    }
+
+    #[test]
+    fn test_export_spaces_out_colliding_chords() {
+        let content = "[Am7]Hal[G]le[C]lujah, [F#m7b5]a[B7]men\n[C][G]\n";
+        let options = PlainTextOptions {
+            section_headers: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            export(content, options),
+            "Am7 G C      F#m7b5 B7\nHal-lelujah, a------men\nC G\n"
+        );
+    }
+
+    #[test]
+    fn test_export_english_headers_and_recall() {
+        let content = "{start_of_chorus}\n[C]Glory\n{end_of_chorus}\n\n\
+                       {start_of_verse: Final}\nAmen\n{end_of_verse}\n{chorus}\n";
+        let options = PlainTextOptions {
+            language: TextLanguage::English,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            export(content, options),
+            "Chorus:\nC\nGlory\n\nFinal:\nAmen\n\nChorus:\nC\nGlory\n"
+        );
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let content = "{start_of_verse: 1}\n[G]О Боже [D/F#]мой, [Em]Ты [C]велик\n{end_of_verse}\n\n\
+                       {start_of_chorus}\n[C]Аллилу[G]йя\n{end_of_chorus}\n";
+        let song = ChordProParser::parse(content);
+        let options = PlainTextOptions {
+            metadata: false,
+            ..Default::default()
+        };
+
+        let text = PlainTextWriter::new(options).write(&song);
+        let (imported, diagnostics) = PlainTextImporter::import_song(&text);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(imported.sections.len(), song.sections.len());
+        for (imported, original) in imported.sections.iter().zip(&song.sections) {
+            assert_eq!(imported.section_type, original.section_type);
+            assert_eq!(imported.lines, original.lines);
+        }
+    }
+}
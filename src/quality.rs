@@ -0,0 +1,567 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Structured chord quality
+//!
+//! Parses quality spellings like `m7`, `maj9`, `7(b9,#11)`, `ø`, `sus4` into
+//! their musical components.
+
+use serde::{Deserialize, Serialize};
+
+use super::Interval;
+
+/// Basic triad
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Triad {
+    #[default]
+    Major,
+    Minor,
+    Diminished,
+    Augmented
+}
+
+/// Seventh on top of the triad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Seventh {
+    /// b7 (`7`, `m7`, `ø`)
+    Minor,
+    /// 7 (`maj7`, `Δ`, `mM7`)
+    Major,
+    /// bb7 (`dim7`, `°7`)
+    Diminished
+}
+
+/// Suspended third
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Suspension {
+    Sus2,
+    Sus4
+}
+
+/// Accidental of an altered chord tone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Accidental {
+    Flat,
+    Sharp
+}
+
+/// Altered chord tone like `b5` or `#9`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Alteration {
+    pub degree:     u8,
+    pub accidental: Accidental
+}
+
+/// Structured chord quality
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordQuality {
+    pub triad:       Triad,
+    pub seventh:     Option<Seventh>,
+    /// Highest stacked extension: 9, 11 or 13
+    pub extension:   Option<u8>,
+    /// Sorted alterations
+    pub alterations: Vec<Alteration>,
+    pub suspension:  Option<Suspension>,
+    /// Added tones without a seventh (`6`, `add9`), sorted
+    pub additions:   Vec<u8>,
+    /// Omitted tones (`no3`, power chord `5`), sorted
+    pub omissions:   Vec<u8>
+}
+
+impl ChordQuality {
+    /// Parse quality spelling (the part of a chord after the root)
+    ///
+    /// Accepts common variants: `m`/`min`/`mi`/`-`, `maj`/`M`/`ma`/`Δ`,
+    /// `dim`/`°`/`o`, `ø`, `aug`/`+`, parenthesized alterations.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut quality = Self::default();
+        let mut major_seventh = false;
+        let mut rest = s.trim();
+
+        // Triad prefix
+        if let Some(after) = strip_any(rest, &["ø", "Ø"]) {
+            quality.triad = Triad::Diminished;
+            quality.seventh = Some(Seventh::Minor);
+            rest = after.strip_prefix('7').unwrap_or(after);
+        } else if let Some(after) = strip_any(rest, &["dim", "°"])
+            .or_else(|| rest.strip_prefix('o').filter(|_| !rest.starts_with("omit")))
+        {
+            quality.triad = Triad::Diminished;
+            if let Some(after) = after.strip_prefix('7') {
+                quality.seventh = Some(Seventh::Diminished);
+                rest = after;
+            } else {
+                rest = after;
+            }
+        } else if let Some(after) = strip_any(rest, &["aug", "+"]) {
+            quality.triad = Triad::Augmented;
+            rest = after;
+        } else if let Some(after) = strip_major(rest) {
+            major_seventh = true;
+            rest = after;
+        } else if let Some(after) = strip_any(rest, &["maj", "Maj", "MAJ", "M"])
+            .filter(|after| split_number(after).0.is_none())
+        {
+            rest = after;
+        } else if let Some(after) = strip_any(rest, &["min", "mi", "m", "-"]) {
+            quality.triad = Triad::Minor;
+            rest = after;
+            if let Some(after) = strip_major(rest.trim_start_matches('(')) {
+                major_seventh = true;
+                rest = after;
+            }
+        }
+
+        let mut first = true;
+        while !rest.is_empty() {
+            if let Some(after) = strip_any(rest, &["(", ")", ",", " "]) {
+                rest = after;
+                continue;
+            }
+
+            if let Some(after) = strip_major(rest) {
+                major_seventh = true;
+                rest = after;
+                first = false;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix("sus") {
+                let (digits, after) = split_number(after);
+                quality.suspension = Some(match digits {
+                    None | Some(4) => Suspension::Sus4,
+                    Some(2) => Suspension::Sus2,
+                    _ => return None
+                });
+                rest = after;
+                first = false;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix("add") {
+                let (degree, after) = split_number(after);
+                quality
+                    .additions
+                    .push(degree.filter(|d| (2..=13).contains(d))?);
+                rest = after;
+                first = false;
+                continue;
+            }
+
+            if let Some(after) = strip_any(rest, &["omit", "no"]) {
+                let (degree, after) = split_number(after);
+                quality
+                    .omissions
+                    .push(degree.filter(|d| matches!(d, 1 | 3 | 5))?);
+                rest = after;
+                first = false;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix("alt") {
+                quality.seventh.get_or_insert(Seventh::Minor);
+                for (degree, accidental) in [
+                    (5, Accidental::Flat),
+                    (5, Accidental::Sharp),
+                    (9, Accidental::Flat),
+                    (9, Accidental::Sharp)
+                ] {
+                    quality.alterations.push(Alteration {
+                        degree,
+                        accidental
+                    });
+                }
+                rest = after;
+                first = false;
+                continue;
+            }
+
+            let accidental = match rest.chars().next() {
+                Some('b' | '♭') => Some(Accidental::Flat),
+                Some('#' | '♯') => Some(Accidental::Sharp),
+                Some('-') if !first => Some(Accidental::Flat),
+                Some('+') if !first => Some(Accidental::Sharp),
+                _ => None
+            };
+            if let Some(accidental) = accidental {
+                let after = &rest[rest.chars().next()?.len_utf8()..];
+                let (degree, after) = split_number(after);
+                let degree = degree.filter(|d| matches!(d, 5 | 9 | 11 | 13))?;
+                quality.alterations.push(Alteration {
+                    degree,
+                    accidental
+                });
+                rest = after;
+                first = false;
+                continue;
+            }
+
+            let (number, after) = split_number(rest);
+            match number? {
+                7 => {
+                    quality.seventh.get_or_insert(Seventh::Minor);
+                }
+                degree @ (9 | 11 | 13) => {
+                    quality.seventh.get_or_insert(Seventh::Minor);
+                    quality.extension = Some(degree);
+                }
+                6 => {
+                    quality.additions.push(6);
+                    if let Some(after) = after.strip_prefix("/9") {
+                        quality.additions.push(9);
+                        rest = after;
+                        first = false;
+                        continue;
+                    }
+                }
+                69 => quality.additions.extend([6, 9]),
+                5 if first && quality.triad == Triad::Major => quality.omissions.push(3),
+                4 if first => quality.suspension = Some(Suspension::Sus4),
+                2 if first => quality.additions.push(2),
+                _ => return None
+            }
+            rest = after;
+            first = false;
+        }
+
+        if major_seventh {
+            quality.seventh = Some(Seventh::Major);
+        }
+
+        quality.alterations.sort();
+        quality.alterations.dedup();
+        quality.additions.sort_unstable();
+        quality.additions.dedup();
+        quality.omissions.sort_unstable();
+        quality.omissions.dedup();
+
+        Some(quality)
+    }
+
+    /// Intervals above the root, ordered by degree
+    ///
+    /// Altered degrees replace the natural ones, `11` implies `9`, `13`
+    /// implies `9` (and `11` on minor chords).
+    pub fn intervals(&self) -> Vec<Interval> {
+        let mut intervals = Vec::new();
+        let altered = |degree| self.alterations.iter().any(|a| a.degree == degree);
+
+        if !self.omissions.contains(&1) {
+            intervals.push(Interval::ROOT);
+        }
+
+        match self.suspension {
+            Some(Suspension::Sus2) => intervals.push(Interval::natural(2)),
+            Some(Suspension::Sus4) => intervals.push(Interval::natural(4)),
+            None if self.omissions.contains(&3) => {}
+            None => intervals.push(match self.triad {
+                Triad::Major | Triad::Augmented => Interval::natural(3),
+                Triad::Minor | Triad::Diminished => Interval::natural(3).altered(-1)
+            })
+        }
+
+        if !altered(5) && !self.omissions.contains(&5) {
+            intervals.push(match self.triad {
+                Triad::Diminished => Interval::natural(5).altered(-1),
+                Triad::Augmented => Interval::natural(5).altered(1),
+                _ => Interval::natural(5)
+            });
+        }
+
+        if let Some(seventh) = self.seventh {
+            intervals.push(match seventh {
+                Seventh::Minor => Interval::natural(7).altered(-1),
+                Seventh::Major => Interval::natural(7),
+                Seventh::Diminished => Interval::natural(7).altered(-2)
+            });
+        }
+
+        let stacked: &[u8] = match self.extension {
+            Some(9) => &[9],
+            Some(11) => &[9, 11],
+            Some(13) if self.is_minor() => &[9, 11, 13],
+            Some(13) => &[9, 13],
+            _ => &[]
+        };
+        for &degree in stacked.iter().chain(&self.additions) {
+            if !altered(degree) {
+                intervals.push(Interval::natural(degree));
+            }
+        }
+
+        for alteration in &self.alterations {
+            let shift = match alteration.accidental {
+                Accidental::Flat => -1,
+                Accidental::Sharp => 1
+            };
+            intervals.push(Interval::natural(alteration.degree).altered(shift));
+        }
+
+        intervals.sort();
+        intervals.dedup();
+        intervals
+    }
+
+    /// Whether the quality makes sense as a chord
+    ///
+    /// Rejects conflicting parts that land on the same pitch, like `m#9`
+    /// (`#9` is the minor third) or `dim7add6`, and a suspension combined
+    /// with a minor or diminished triad.
+    pub fn is_valid(&self) -> bool {
+        if self.suspension.is_some() && matches!(self.triad, Triad::Minor | Triad::Diminished) {
+            return false;
+        }
+
+        let mut pitches: Vec<u8> = self.intervals().iter().map(|i| i.semitones % 12).collect();
+        let count = pitches.len();
+        pitches.sort_unstable();
+        pitches.dedup();
+        pitches.len() == count
+    }
+
+    /// Triad including an altered fifth (`m7b5` is diminished, `7#5` augmented)
+    pub fn sounding_triad(&self) -> Triad {
+        let fifth = |accidental| {
+            self.alterations.contains(&Alteration {
+                degree: 5,
+                accidental
+            })
+        };
+
+        match self.triad {
+            Triad::Minor if fifth(Accidental::Flat) => Triad::Diminished,
+            Triad::Major if fifth(Accidental::Sharp) => Triad::Augmented,
+            triad => triad
+        }
+    }
+
+    pub fn is_minor(&self) -> bool {
+        self.triad == Triad::Minor
+    }
+
+    /// Power chord (root and fifth only)
+    pub fn is_power_chord(&self) -> bool {
+        self.omissions == [3] && self.seventh.is_none() && self.additions.is_empty()
+    }
+}
+
+/// Strip the first matching prefix
+fn strip_any<'a>(s: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes.iter().find_map(|p| s.strip_prefix(p))
+}
+
+/// Strip a major-seventh marker (`maj`, `ma`, `M`, `Δ`, `△`)
+///
+/// Without a following number (`Cmaj`) it only means "major" and is not
+/// treated as a seventh unless it is `Δ`.
+fn strip_major(s: &str) -> Option<&str> {
+    if let Some(after) = strip_any(s, &["Δ", "△", "^"]) {
+        return Some(after.strip_prefix('7').unwrap_or(after));
+    }
+
+    let after = strip_any(s, &["maj", "Maj", "MAJ", "ma", "M"])?;
+    match split_number(after) {
+        (Some(7), after) => Some(after),
+        (Some(9 | 11 | 13), _) => Some(after),
+        _ => None
+    }
+}
+
+/// Split a leading decimal number
+fn split_number(s: &str) -> (Option<u8>, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    (s[..end].parse().ok(), &s[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> ChordQuality {
+        ChordQuality::parse(s).unwrap()
+    }
+
+    fn alt(degree: u8, accidental: Accidental) -> Alteration {
+        Alteration {
+            degree,
+            accidental
+        }
+    }
+
+    #[test]
+    fn test_parse_triads() {
+        assert_eq!(parse(""), ChordQuality::default());
+        assert_eq!(parse("m").triad, Triad::Minor);
+        assert_eq!(parse("min").triad, Triad::Minor);
+        assert_eq!(parse("mi").triad, Triad::Minor);
+        assert_eq!(parse("-").triad, Triad::Minor);
+        assert_eq!(parse("dim").triad, Triad::Diminished);
+        assert_eq!(parse("°").triad, Triad::Diminished);
+        assert_eq!(parse("o").triad, Triad::Diminished);
+        assert_eq!(parse("aug").triad, Triad::Augmented);
+        assert_eq!(parse("+").triad, Triad::Augmented);
+        assert_eq!(parse("maj"), ChordQuality::default());
+    }
+
+    #[test]
+    fn test_parse_sevenths() {
+        assert_eq!(parse("7").seventh, Some(Seventh::Minor));
+        assert_eq!(parse("m7").seventh, Some(Seventh::Minor));
+        assert_eq!(parse("maj7").seventh, Some(Seventh::Major));
+        assert_eq!(parse("M7").seventh, Some(Seventh::Major));
+        assert_eq!(parse("ma7").seventh, Some(Seventh::Major));
+        assert_eq!(parse("Δ").seventh, Some(Seventh::Major));
+        assert_eq!(parse("Δ7").seventh, Some(Seventh::Major));
+        assert_eq!(parse("dim7").seventh, Some(Seventh::Diminished));
+        assert_eq!(parse("°7").seventh, Some(Seventh::Diminished));
+
+        let half = parse("ø");
+        assert_eq!(half.triad, Triad::Diminished);
+        assert_eq!(half.seventh, Some(Seventh::Minor));
+        assert_eq!(parse("ø7"), half);
+
+        let minor_major = parse("mM7");
+        assert_eq!(minor_major.triad, Triad::Minor);
+        assert_eq!(minor_major.seventh, Some(Seventh::Major));
+        assert_eq!(parse("m(maj7)"), minor_major);
+        assert_eq!(parse("-Δ7"), minor_major);
+    }
+
+    #[test]
+    fn test_parse_extensions() {
+        let ninth = parse("9");
+        assert_eq!(ninth.seventh, Some(Seventh::Minor));
+        assert_eq!(ninth.extension, Some(9));
+
+        let maj9 = parse("maj9");
+        assert_eq!(maj9.seventh, Some(Seventh::Major));
+        assert_eq!(maj9.extension, Some(9));
+
+        let m11 = parse("m11");
+        assert_eq!(m11.triad, Triad::Minor);
+        assert_eq!(m11.extension, Some(11));
+
+        assert_eq!(parse("13").extension, Some(13));
+        assert_eq!(parse("Δ9"), maj9);
+    }
+
+    #[test]
+    fn test_parse_alterations() {
+        let q = parse("7b9");
+        assert_eq!(q.alterations, vec![alt(9, Accidental::Flat)]);
+
+        let q = parse("7(#11,b9)");
+        assert_eq!(
+            q.alterations,
+            vec![alt(9, Accidental::Flat), alt(11, Accidental::Sharp)]
+        );
+
+        let half = parse("m7b5");
+        assert_eq!(half.triad, Triad::Minor);
+        assert_eq!(half.alterations, vec![alt(5, Accidental::Flat)]);
+
+        assert_eq!(parse("7-5").alterations, vec![alt(5, Accidental::Flat)]);
+        assert_eq!(parse("7+5").alterations, vec![alt(5, Accidental::Sharp)]);
+        assert_eq!(parse("7♯9").alterations, vec![alt(9, Accidental::Sharp)]);
+        assert_eq!(parse("7alt").alterations.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_suspensions_additions_omissions() {
+        assert_eq!(parse("sus").suspension, Some(Suspension::Sus4));
+        assert_eq!(parse("sus4").suspension, Some(Suspension::Sus4));
+        assert_eq!(parse("sus2").suspension, Some(Suspension::Sus2));
+        assert_eq!(parse("4").suspension, Some(Suspension::Sus4));
+
+        let q = parse("7sus4");
+        assert_eq!(q.seventh, Some(Seventh::Minor));
+        assert_eq!(q.suspension, Some(Suspension::Sus4));
+
+        assert_eq!(parse("add9").additions, vec![9]);
+        assert_eq!(parse("2").additions, vec![2]);
+        assert_eq!(parse("6").additions, vec![6]);
+        assert_eq!(parse("m6").additions, vec![6]);
+        assert_eq!(parse("69").additions, vec![6, 9]);
+        assert_eq!(parse("6/9").additions, vec![6, 9]);
+        assert_eq!(parse("add9(no3)").omissions, vec![3]);
+        assert_eq!(parse("omit5").omissions, vec![5]);
+    }
+
+    #[test]
+    fn test_parse_power_chord() {
+        let q = parse("5");
+        assert!(q.is_power_chord());
+        assert!(!parse("").is_power_chord());
+        assert!(parse("m").is_minor());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(ChordQuality::parse("m/X").is_none());
+        assert!(ChordQuality::parse("xyz").is_none());
+        assert!(ChordQuality::parse("add").is_none());
+        assert!(ChordQuality::parse("sus3").is_none());
+        assert!(ChordQuality::parse("7b4").is_none());
+        assert!(ChordQuality::parse("m3").is_none());
+    }
+
+    fn intervals(s: &str) -> Vec<String> {
+        parse(s)
+            .intervals()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_intervals() {
+        assert_eq!(intervals(""), ["1", "3", "5"]);
+        assert_eq!(intervals("m7"), ["1", "b3", "5", "b7"]);
+        assert_eq!(intervals("maj9"), ["1", "3", "5", "7", "9"]);
+        assert_eq!(intervals("ø"), ["1", "b3", "b5", "b7"]);
+        assert_eq!(intervals("dim7"), ["1", "b3", "b5", "bb7"]);
+        assert_eq!(intervals("aug"), ["1", "3", "#5"]);
+        assert_eq!(intervals("7sus4"), ["1", "4", "5", "b7"]);
+        assert_eq!(intervals("7b5#9"), ["1", "3", "b5", "b7", "#9"]);
+        assert_eq!(intervals("13"), ["1", "3", "5", "b7", "9", "13"]);
+        assert_eq!(intervals("m11"), ["1", "b3", "5", "b7", "9", "11"]);
+        assert_eq!(intervals("6/9"), ["1", "3", "5", "6", "9"]);
+        assert_eq!(intervals("5"), ["1", "5"]);
+        assert_eq!(intervals("add9(no3)"), ["1", "5", "9"]);
+    }
+
+    #[test]
+    fn test_sounding_triad() {
+        assert_eq!(parse("m7b5").sounding_triad(), Triad::Diminished);
+        assert_eq!(parse("7#5").sounding_triad(), Triad::Augmented);
+        assert_eq!(parse("7b5").sounding_triad(), Triad::Major);
+        assert_eq!(parse("m7").sounding_triad(), Triad::Minor);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(parse("7#9").is_valid());
+        assert!(parse("m7b5").is_valid());
+        assert!(parse("13").is_valid());
+        assert!(!parse("m#9").is_valid());
+        assert!(!parse("dim7add6").is_valid());
+        assert!(!parse("msus4").is_valid());
+    }
+
+    #[test]
+    fn test_equivalent_spellings_are_equal() {
+        assert_eq!(parse("maj7"), parse("Δ7"));
+        assert_eq!(parse("min7"), parse("-7"));
+        assert_eq!(parse("7(b9,#9)"), parse("7#9b9"));
+    }
+}
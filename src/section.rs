@@ -3,10 +3,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::PositionedChord;
+use super::{ChordGrid, PositionedChord};
 
 /// Parsed song line with chords positioned above text
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct SongLine {
     pub text:   String,
@@ -14,12 +14,19 @@ pub struct SongLine {
 }
 
 /// Song section (verse, chorus, bridge, etc.)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 pub struct SongSection {
     pub section_type: SongSectionType,
     pub label:        Option<String>,
-    pub lines:        Vec<SongLine>
+    pub lines:        Vec<SongLine>,
+    /// Index of the section repeated here (`{chorus}`); such sections have no
+    /// lines of their own
+    #[serde(default)]
+    pub recall:       Option<usize>,
+    /// Structured content of `{start_of_grid}` sections
+    #[serde(default)]
+    pub grid:         Option<ChordGrid>
 }
 
 /// Section type
@@ -36,6 +43,16 @@ pub enum SongSectionType {
     Interlude,
     Tag,
     Ending,
+    /// `{start_of_tab}`, kept verbatim
+    Tab,
+    /// `{start_of_grid}`, kept verbatim and parsed into a chord grid
+    Grid,
+    /// `{start_of_abc}`, ABC notation kept verbatim
+    Abc,
+    /// `{start_of_ly}`, LilyPond notation kept verbatim
+    Lilypond,
+    /// `{start_of_textblock}`, kept verbatim
+    Textblock,
     Other
 }
 
@@ -51,9 +68,42 @@ impl SongSectionType {
             Self::Interlude => "Проигрыш",
             Self::Tag => "Тег",
             Self::Ending => "Кода",
+            Self::Tab => "Табулатура",
+            Self::Grid => "Сетка аккордов",
+            Self::Abc => "Ноты ABC",
+            Self::Lilypond => "Ноты LilyPond",
+            Self::Textblock => "Текст",
             Self::Other => ""
         }
     }
+
+    pub fn name_en(&self) -> &'static str {
+        match self {
+            Self::Verse => "Verse",
+            Self::Chorus => "Chorus",
+            Self::Bridge => "Bridge",
+            Self::PreChorus => "Pre-Chorus",
+            Self::Intro => "Intro",
+            Self::Outro => "Outro",
+            Self::Interlude => "Interlude",
+            Self::Tag => "Tag",
+            Self::Ending => "Ending",
+            Self::Tab => "Tab",
+            Self::Grid => "Grid",
+            Self::Abc => "ABC",
+            Self::Lilypond => "LilyPond",
+            Self::Textblock => "Text",
+            Self::Other => ""
+        }
+    }
+
+    /// Whether section content is kept verbatim instead of parsed as lyrics
+    pub fn is_verbatim(&self) -> bool {
+        matches!(
+            self,
+            Self::Tab | Self::Grid | Self::Abc | Self::Lilypond | Self::Textblock
+        )
+    }
 }
 
 #[cfg(test)]
@@ -71,6 +121,30 @@ mod tests {
         assert_eq!(SongSectionType::Interlude.name_ru(), "Проигрыш");
         assert_eq!(SongSectionType::Tag.name_ru(), "Тег");
         assert_eq!(SongSectionType::Ending.name_ru(), "Кода");
+        assert_eq!(SongSectionType::Tab.name_ru(), "Табулатура");
+        assert_eq!(SongSectionType::Grid.name_ru(), "Сетка аккордов");
+        assert_eq!(SongSectionType::Abc.name_ru(), "Ноты ABC");
+        assert_eq!(SongSectionType::Lilypond.name_ru(), "Ноты LilyPond");
+        assert_eq!(SongSectionType::Textblock.name_ru(), "Текст");
         assert_eq!(SongSectionType::Other.name_ru(), "");
     }
+
+    #[test]
+    fn test_section_type_name_en() {
+        assert_eq!(SongSectionType::Verse.name_en(), "Verse");
+        assert_eq!(SongSectionType::PreChorus.name_en(), "Pre-Chorus");
+        assert_eq!(SongSectionType::Ending.name_en(), "Ending");
+        assert_eq!(SongSectionType::Other.name_en(), "");
+    }
+
+    #[test]
+    fn test_section_type_is_verbatim() {
+        assert!(SongSectionType::Tab.is_verbatim());
+        assert!(SongSectionType::Grid.is_verbatim());
+        assert!(SongSectionType::Abc.is_verbatim());
+        assert!(SongSectionType::Lilypond.is_verbatim());
+        assert!(SongSectionType::Textblock.is_verbatim());
+        assert!(!SongSectionType::Verse.is_verbatim());
+        assert!(!SongSectionType::Other.is_verbatim());
+    }
 }
@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Chord simplification
+//!
+//! Reduces charts for beginners: extensions, additions, alterations and
+//! suspensions are dropped, slash basses removed, and optionally a capo is
+//! chosen so that the simplified chords become easy open shapes.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Chord, ChordDefinition, ChordProParser, ChordQuality, LineNode, ParsedSong, Seventh,
+    SyntaxTree, Triad, capo_view, sounding_key, suggest_capo, transpose::transpose_chord_in
+};
+
+/// How much of a chord is kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Complexity {
+    /// Major and minor triads only (`F#m7b5` → `F#m`, `Caug` → `C`)
+    #[default]
+    Triads,
+    /// Triads and sevenths (`Cmaj9` → `Cmaj7`, `G13` → `G7`, `Bm7b5` kept)
+    Sevenths
+}
+
+/// Simplification options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct SimplifyOptions {
+    pub level:       Complexity,
+    /// Keep slash basses (`G/B`)
+    pub keep_bass:   bool,
+    /// Move the capo so that the chords are played with the most open shapes
+    pub capo_shapes: bool
+}
+
+/// Reduce a chord to the complexity level
+///
+/// Chords with an unrecognized quality are returned unchanged.
+pub fn simplify_chord(chord: &Chord, options: &SimplifyOptions) -> Chord {
+    let Some(structure) = &chord.structure else {
+        return chord.clone();
+    };
+
+    let quality = match options.level {
+        Complexity::Triads => triad_quality(structure),
+        Complexity::Sevenths => seventh_quality(structure)
+    };
+
+    Chord {
+        root:      chord.root.clone(),
+        structure: ChordQuality::parse(quality),
+        quality:   quality.to_string(),
+        bass:      chord.bass.clone().filter(|_| options.keep_bass)
+    }
+}
+
+/// Simplified copy of a song
+///
+/// Chord definitions are kept only for chords that still appear.
+pub fn simplify_song(song: &ParsedSong, options: &SimplifyOptions) -> ParsedSong {
+    let mut simple = song.clone();
+    simple.map_chords(|chord| simplify_chord(chord, options));
+
+    let names: Vec<String> = simple.chords().iter().map(|c| c.to_string()).collect();
+    simple.definitions.retain(|d| names.contains(&d.name));
+
+    match options.capo_shapes.then(|| best_capo(&simple)).flatten() {
+        Some(capo) => capo_view(&simple, capo),
+        None => simple
+    }
+}
+
+/// Simplify chords in ChordPro content, keeping everything else as written
+///
+/// Chord definitions are kept only for chords that still appear. With
+/// `capo_shapes` the `{capo}` directive is set to the chosen position and
+/// the definitions move with the chords.
+pub fn simplify_content(content: &str, options: &SimplifyOptions) -> String {
+    let mut tree = SyntaxTree::parse(content);
+
+    let song = simplify_song(
+        &ChordProParser::parse(content),
+        &SimplifyOptions {
+            capo_shapes: false,
+            ..*options
+        }
+    );
+    let capo = options.capo_shapes.then(|| best_capo(&song)).flatten();
+    let shift = capo.map_or(0, |capo| song.capo.unwrap_or(0) - capo as i32);
+    let shape_key =
+        capo.and_then(|capo| sounding_key(&song).map(|key| key.transpose(-(capo as i32))));
+
+    for token in tree.chords_mut() {
+        if let Some(chord) = Chord::parse(token) {
+            let simple = simplify_chord(&chord, options);
+            *token = transpose_chord_in(&simple, shift, shape_key.as_ref()).to_string();
+        }
+    }
+
+    let names: Vec<String> = song.chords().iter().map(|c| c.to_string()).collect();
+    let use_flats = shape_key.is_some_and(|key| key.uses_flats());
+    tree.lines.retain_mut(|line| {
+        let LineNode::Directive(directive) = &mut line.node else {
+            return true;
+        };
+        if !matches!(directive.normalized_name().as_str(), "define" | "chord") {
+            return true;
+        }
+        let Some(definition) = directive.value.as_deref().and_then(ChordDefinition::parse) else {
+            return true;
+        };
+        if !names.contains(&definition.name) {
+            return false;
+        }
+        if shift != 0 {
+            match definition.transpose(shift, use_flats) {
+                Some(definition) => directive.value = Some(definition.to_string()),
+                None => return false
+            }
+        }
+        true
+    });
+    if let Some(capo) = capo {
+        tree.set_directive("capo", &capo.to_string());
+    }
+
+    tree.to_string()
+}
+
+/// Capo position with the most open shapes, when it differs from the written
+/// one
+fn best_capo(song: &ParsedSong) -> Option<u8> {
+    let best = suggest_capo(song).first()?.capo;
+    (best as i32 != song.capo.unwrap_or(0)).then_some(best)
+}
+
+fn triad_quality(structure: &ChordQuality) -> &'static str {
+    match structure.sounding_triad() {
+        Triad::Minor | Triad::Diminished if structure.suspension.is_none() => "m",
+        _ => ""
+    }
+}
+
+fn seventh_quality(structure: &ChordQuality) -> &'static str {
+    let triad = if structure.suspension.is_some() {
+        Triad::Major
+    } else {
+        structure.sounding_triad()
+    };
+
+    match (triad, structure.seventh) {
+        (Triad::Major, None) => "",
+        (Triad::Major, Some(Seventh::Major)) => "maj7",
+        (Triad::Major, Some(_)) => "7",
+        (Triad::Minor, None) => "m",
+        (Triad::Minor, Some(Seventh::Major)) => "mMaj7",
+        (Triad::Minor, Some(_)) => "m7",
+        (Triad::Diminished, None) => "dim",
+        (Triad::Diminished, Some(Seventh::Minor)) => "m7b5",
+        (Triad::Diminished, Some(_)) => "dim7",
+        (Triad::Augmented, None) => "aug",
+        (Triad::Augmented, Some(_)) => "aug7"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simplify(s: &str, level: Complexity) -> String {
+        let options = SimplifyOptions {
+            level,
+            ..Default::default()
+        };
+        simplify_chord(&Chord::parse(s).unwrap(), &options).to_string()
+    }
+
+    #[test]
+    fn test_simplify_to_triads() {
+        assert_eq!(simplify("Cmaj9", Complexity::Triads), "C");
+        assert_eq!(simplify("G/B", Complexity::Triads), "G");
+        assert_eq!(simplify("Dsus4", Complexity::Triads), "D");
+        assert_eq!(simplify("Asus2", Complexity::Triads), "A");
+        assert_eq!(simplify("F#m7b5/C", Complexity::Triads), "F#m");
+        assert_eq!(simplify("Em11", Complexity::Triads), "Em");
+        assert_eq!(simplify("C#dim7", Complexity::Triads), "C#m");
+        assert_eq!(simplify("E7#9", Complexity::Triads), "E");
+        assert_eq!(simplify("D5", Complexity::Triads), "D");
+        assert_eq!(simplify("Cxyz", Complexity::Triads), "Cxyz");
+    }
+
+    #[test]
+    fn test_simplify_to_sevenths() {
+        assert_eq!(simplify("Cmaj9", Complexity::Sevenths), "Cmaj7");
+        assert_eq!(simplify("G13", Complexity::Sevenths), "G7");
+        assert_eq!(simplify("Am11", Complexity::Sevenths), "Am7");
+        assert_eq!(simplify("Bm7b5", Complexity::Sevenths), "Bm7b5");
+        assert_eq!(simplify("Bø", Complexity::Sevenths), "Bm7b5");
+        assert_eq!(simplify("G7sus4", Complexity::Sevenths), "G7");
+        assert_eq!(simplify("Cadd9", Complexity::Sevenths), "C");
+        assert_eq!(simplify("C6", Complexity::Sevenths), "C");
+        assert_eq!(simplify("E7#5", Complexity::Sevenths), "Eaug7");
+    }
+
+    #[test]
+    fn test_keep_bass() {
+        let options = SimplifyOptions {
+            keep_bass: true,
+            ..Default::default()
+        };
+        let chord = simplify_chord(&Chord::parse("Gmaj7/B").unwrap(), &options);
+        assert_eq!(chord.to_string(), "G/B");
+        assert!(chord.structure.is_some());
+    }
+
+    #[test]
+    fn test_simplify_song() {
+        let song = ChordProParser::parse(
+            "{define: Cmaj9 base-fret 1 frets x 3 2 4 3 x}\n{define: G base-fret 1 frets 3 2 0 0 0 3}\n[Cmaj9]Hello [G/B]world [Am7]again"
+        );
+        let simple = simplify_song(&song, &SimplifyOptions::default());
+        let chords: Vec<String> = simple.chords().iter().map(|c| c.to_string()).collect();
+        assert_eq!(chords, ["C", "G", "Am"]);
+        assert_eq!(simple.definitions.len(), 1);
+        assert_eq!(simple.definitions[0].name, "G");
+    }
+
+    #[test]
+    fn test_simplify_song_with_capo_shapes() {
+        let song =
+            ChordProParser::parse("{key: Eb}\n[Ebmaj7]Hello [Ab/C]world [Bb7sus4]again [Cm9]");
+        let simple = simplify_song(
+            &song,
+            &SimplifyOptions {
+                capo_shapes: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(simple.capo, Some(1));
+        let chords: Vec<String> = simple.chords().iter().map(|c| c.to_string()).collect();
+        assert_eq!(chords, ["D", "G", "A", "Bm"]);
+    }
+
+    #[test]
+    fn test_simplify_content() {
+        let content = "{title: Song}\n# keep [this]\n[Cmaj9]Hello [G/B]world [*riff] [N.C.]\n";
+        assert_eq!(
+            simplify_content(content, &SimplifyOptions::default()),
+            "{title: Song}\n# keep [this]\n[C]Hello [G]world [*riff] [N.C.]\n"
+        );
+
+        let content = "{key: Bb}\n[Bbsus4]Hello [F/A]world [Gm7]again\n";
+        assert_eq!(
+            simplify_content(
+                content,
+                &SimplifyOptions {
+                    capo_shapes: true,
+                    ..Default::default()
+                }
+            ),
+            "{key: Bb}\n{capo: 3}\n[G]Hello [D]world [Em]again\n"
+        );
+    }
+
+    #[test]
+    fn test_simplify_content_definitions() {
+        let content = "{define: Cmaj9 base-fret 1 frets x 3 2 4 3 x}\n{define: G base-fret 1 frets 3 2 0 0 0 3}\n[Cmaj9]Hello [G/B]world\n";
+        assert_eq!(
+            simplify_content(content, &SimplifyOptions::default()),
+            "{define: G base-fret 1 frets 3 2 0 0 0 3}\n[C]Hello [G]world\n"
+        );
+
+        let content = "{key: Bb}\n{define: Bb base-fret 1 frets x 1 3 3 3 1}\n[Bbsus4]Hello [F/A]world [Gm7]again\n";
+        assert_eq!(
+            simplify_content(
+                content,
+                &SimplifyOptions {
+                    capo_shapes: true,
+                    ..Default::default()
+                }
+            ),
+            "{key: Bb}\n{define: G base-fret 10 frets x 1 3 3 3 1}\n{capo: 3}\n[G]Hello [D]world [Em]again\n"
+        );
+    }
+}
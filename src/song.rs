@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
-use super::{SongCategory, SongTag};
+use super::{ChordProParser, SongCategory, SongTag};
 
 /// Full song with all details
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +87,17 @@ pub struct CreateSong {
     pub source_url: Option<String>
 }
 
+impl CreateSong {
+    /// Fill `original_key` from `{key}` or detected chords when it is missing
+    pub fn fill_original_key(&mut self) {
+        if self.original_key.is_none() {
+            self.original_key = ChordProParser::parse(&self.content)
+                .estimated_key()
+                .map(|key| key.to_string());
+        }
+    }
+}
+
 /// Update song request
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -110,3 +121,49 @@ pub struct UpdateSong {
     pub categories:     Option<Vec<SongCategory>>,
     pub tag_ids:        Option<Vec<Uuid>>
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(content: &str, original_key: Option<&str>) -> CreateSong {
+        CreateSong {
+            songbook_id:    None,
+            number:         None,
+            title:          "Song".to_string(),
+            title_alt:      None,
+            author_lyrics:  None,
+            author_music:   None,
+            translator:     None,
+            year_written:   None,
+            copyright:      None,
+            original_key:   original_key.map(str::to_string),
+            tempo:          None,
+            time_signature: None,
+            content:        content.to_string(),
+            categories:     Vec::new(),
+            tag_ids:        Vec::new(),
+            source_url:     None
+        }
+    }
+
+    fn filled(content: &str, original_key: Option<&str>) -> Option<String> {
+        let mut song = song(content, original_key);
+        song.fill_original_key();
+        song.original_key
+    }
+
+    #[test]
+    fn test_fill_original_key() {
+        assert_eq!(
+            filled("{key: D}\n[G]Hello", Some("E")).as_deref(),
+            Some("E")
+        );
+        assert_eq!(filled("{key: Bbm}\n[G]Hello", None).as_deref(), Some("Bbm"));
+        assert_eq!(
+            filled("[C]Hello [F]world [G]again [C]", None).as_deref(),
+            Some("C")
+        );
+        assert_eq!(filled("Just lyrics", None), None);
+    }
+}
@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! SVG chord diagrams
+//!
+//! Diagrams are rendered to standalone SVG strings so that PDF export, the
+//! website and printed songbooks draw identical pictures. Lines, dots and
+//! labels use `currentColor` and follow the surrounding text; finger numbers
+//! inside dots and white piano keys are white, and pressed keys use a fixed
+//! blue.
+
+use super::{Chord, Tuning, Voicing, interchange::escape_xml};
+
+/// Distance between strings
+const STRING_GAP: u32 = 20;
+
+/// Distance between frets
+const FRET_GAP: u32 = 24;
+
+/// Frets drawn at least
+const MIN_FRETS: u8 = 4;
+
+/// Space around the fretboard
+const MARGIN: u32 = 24;
+
+/// Height of the title line
+const TITLE_HEIGHT: u32 = 22;
+
+/// Space above the nut for open and muted markers
+const MARKER_HEIGHT: u32 = 16;
+
+const DOT_RADIUS: u32 = 7;
+
+const WHITE_KEY_WIDTH: u32 = 16;
+const WHITE_KEY_HEIGHT: u32 = 64;
+const BLACK_KEY_WIDTH: u32 = 10;
+const BLACK_KEY_HEIGHT: u32 = 40;
+
+/// Keyboard range in semitones from C
+const KEYBOARD_KEYS: u8 = 24;
+
+/// Semitones of black keys within an octave
+const BLACK_KEYS: [u8; 5] = [1, 3, 6, 8, 10];
+
+/// Fill of pressed piano keys
+const HIGHLIGHT: &str = "#4a90d9";
+
+/// Render a fretboard diagram of a voicing
+///
+/// Strings are drawn left to right from the lowest, with string names below
+/// and the optional title above.
+pub fn voicing_svg(voicing: &Voicing, tuning: &Tuning, title: Option<&str>) -> String {
+    let strings = voicing.frets.len().max(tuning.strings.len()).max(1) as u32;
+    let frets = voicing
+        .frets
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(MIN_FRETS) as u32;
+
+    let top = MARGIN + title.map_or(0, |_| TITLE_HEIGHT) + MARKER_HEIGHT;
+    let width = MARGIN * 2 + (strings - 1) * STRING_GAP;
+    let board_height = frets * FRET_GAP;
+    let height = top + board_height + MARGIN + 12;
+    let x = |string: u32| MARGIN + string * STRING_GAP;
+    let y = |fret: u32| top + fret * FRET_GAP;
+
+    let mut out = open_svg(width, height);
+
+    if let Some(title) = title {
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"16\" font-weight=\"bold\">{}</text>\n",
+            width / 2,
+            MARGIN + 8,
+            escape_xml(title)
+        ));
+    }
+
+    for fret in 0..=frets {
+        out.push_str(&line(x(0), y(fret), x(strings - 1), y(fret), 1));
+    }
+    for string in 0..strings {
+        out.push_str(&line(x(string), y(0), x(string), y(frets), 1));
+    }
+
+    if voicing.base_fret <= 1 {
+        out.push_str(&line(x(0), y(0), x(strings - 1), y(0), 4));
+    } else {
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" font-size=\"12\">{}fr</text>\n",
+            x(0) - 6,
+            y(0) + FRET_GAP / 2 + 4,
+            voicing.base_fret
+        ));
+    }
+
+    let marker_y = y(0) - MARKER_HEIGHT / 2;
+    for (string, fret) in voicing.frets.iter().enumerate() {
+        let cx = x(string as u32);
+        match fret {
+            None => out.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"12\">×</text>\n",
+                cx,
+                marker_y + 4
+            )),
+            Some(0) => out.push_str(&format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"none\" stroke=\"currentColor\"/>\n",
+                cx, marker_y
+            )),
+            Some(_) => {}
+        }
+    }
+
+    if let Some(barre) = voicing.barre {
+        let cy = y(barre.fret as u32) - FRET_GAP / 2;
+        out.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"currentColor\"/>\n",
+            x(barre.from as u32) - DOT_RADIUS,
+            cy - DOT_RADIUS,
+            (barre.to - barre.from) as u32 * STRING_GAP + DOT_RADIUS * 2,
+            DOT_RADIUS * 2,
+            DOT_RADIUS
+        ));
+    }
+
+    for (string, fret) in voicing.frets.iter().enumerate() {
+        let Some(fret) = fret.filter(|f| *f > 0) else {
+            continue;
+        };
+        let (cx, cy) = (x(string as u32), y(fret as u32) - FRET_GAP / 2);
+        out.push_str(&format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"currentColor\"/>\n",
+            cx, cy, DOT_RADIUS
+        ));
+        if let Some(finger) = voicing.fingers.get(string).copied().flatten() {
+            out.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"10\" fill=\"#fff\">{}</text>\n",
+                cx,
+                cy + 4,
+                finger
+            ));
+        }
+    }
+
+    for (string, note) in tuning.strings.iter().enumerate() {
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"11\">{}</text>\n",
+            x(string as u32),
+            y(frets) + 16,
+            note.to_sharp_string()
+        ));
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Render a two-octave keyboard with the chord notes highlighted
+///
+/// Notes are stacked upwards from the bass (or root). `None` when the chord
+/// quality is not recognized.
+pub fn keyboard_svg(chord: &Chord) -> Option<String> {
+    let keys = keyboard_keys(chord)?;
+
+    let white_keys: Vec<u8> = (0..KEYBOARD_KEYS)
+        .filter(|k| !BLACK_KEYS.contains(&(k % 12)))
+        .collect();
+    let width = white_keys.len() as u32 * WHITE_KEY_WIDTH + 2;
+    let mut out = open_svg(width, WHITE_KEY_HEIGHT + 2);
+
+    for (index, key) in white_keys.iter().enumerate() {
+        let fill = if keys.contains(key) {
+            HIGHLIGHT
+        } else {
+            "#fff"
+        };
+        out.push_str(&format!(
+            "<rect x=\"{}\" y=\"1\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"currentColor\"/>\n",
+            1 + index as u32 * WHITE_KEY_WIDTH,
+            WHITE_KEY_WIDTH,
+            WHITE_KEY_HEIGHT,
+            fill
+        ));
+    }
+
+    for key in (0..KEYBOARD_KEYS).filter(|k| BLACK_KEYS.contains(&(k % 12))) {
+        let white_before = white_keys.iter().filter(|w| **w < key).count() as u32;
+        let fill = if keys.contains(&key) {
+            HIGHLIGHT
+        } else {
+            "currentColor"
+        };
+        out.push_str(&format!(
+            "<rect x=\"{}\" y=\"1\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"currentColor\"/>\n",
+            1 + white_before * WHITE_KEY_WIDTH - BLACK_KEY_WIDTH / 2,
+            BLACK_KEY_WIDTH,
+            BLACK_KEY_HEIGHT,
+            fill
+        ));
+    }
+
+    out.push_str("</svg>\n");
+    Some(out)
+}
+
+/// Keys (semitones from the lowest C) to press for a chord, in close
+/// position above the bass
+fn keyboard_keys(chord: &Chord) -> Option<Vec<u8>> {
+    let notes = chord.notes()?;
+    let bass = notes.first()?.note().to_semitone();
+
+    let mut keys: Vec<u8> = notes
+        .iter()
+        .map(|note| bass + (note.note().to_semitone() + 12 - bass) % 12)
+        .map(|key| if key >= KEYBOARD_KEYS { key - 12 } else { key })
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    Some(keys)
+}
+
+fn open_svg(width: u32, height: u32) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" fill=\"currentColor\">\n"
+    )
+}
+
+fn line(x1: u32, y1: u32, x2: u32, y2: u32, width: u32) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"currentColor\" stroke-width=\"{}\"/>\n",
+        x1, y1, x2, y2, width
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Instrument, chord_voicing};
+
+    fn voicing(chord: &str) -> Voicing {
+        chord_voicing(&Chord::parse(chord).unwrap(), Instrument::Guitar, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_voicing_svg() {
+        let svg = voicing_svg(&voicing("C"), &Instrument::Guitar.tuning(), Some("C"));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains(">C</text>"));
+        assert_eq!(svg.matches(">×</text>").count(), 1);
+        assert_eq!(svg.matches("r=\"4\" fill=\"none\"").count(), 2);
+        assert_eq!(svg.matches("r=\"7\"").count(), 3);
+        assert!(svg.contains("stroke-width=\"4\""));
+        assert!(!svg.contains("<rect"));
+    }
+
+    #[test]
+    fn test_voicing_svg_barre_and_base_fret() {
+        let definition = crate::ChordDefinition::parse("D base-fret 5 frets x 1 3 3 3 1").unwrap();
+        let voicing = Voicing::from_definition(&definition).unwrap();
+        let svg = voicing_svg(&voicing, &Instrument::Guitar.tuning(), None);
+        assert!(svg.contains(">5fr</text>"));
+        assert!(svg.contains("<rect"));
+        assert!(!svg.contains("stroke-width=\"4\""));
+    }
+
+    #[test]
+    fn test_voicing_svg_escapes_title() {
+        let svg = voicing_svg(
+            &voicing("G"),
+            &Instrument::Guitar.tuning(),
+            Some("G <alt> & \"x\"")
+        );
+        assert!(svg.contains("G &lt;alt&gt; &amp; &quot;x&quot;"));
+    }
+
+    #[test]
+    fn test_keyboard_keys() {
+        let keys = |s: &str| keyboard_keys(&Chord::parse(s).unwrap()).unwrap();
+        assert_eq!(keys("C"), [0, 4, 7]);
+        assert_eq!(keys("Am"), [9, 12, 16]);
+        assert_eq!(keys("C/E"), [4, 7, 12]);
+        assert_eq!(keys("Bb7"), [10, 14, 17, 20]);
+        assert!(keyboard_keys(&Chord::parse("Cxyz").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_keyboard_svg() {
+        let svg = keyboard_svg(&Chord::parse("F#m").unwrap()).unwrap();
+        assert_eq!(svg.matches("<rect").count(), 24);
+        assert_eq!(svg.matches(HIGHLIGHT).count(), 3);
+    }
+}
@@ -0,0 +1,538 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Lossless ChordPro syntax tree
+//!
+//! Keeps every byte of the source: comments, whitespace, line endings,
+//! directive spelling and unknown directives. Printing the tree reproduces
+//! the input exactly, so edits only change the bytes they touch.
+
+use std::fmt;
+
+/// Lossless syntax tree of a ChordPro document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyntaxTree {
+    pub lines: Vec<SyntaxLine>
+}
+
+/// Single source line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxLine {
+    /// Leading whitespace
+    pub indent:   String,
+    pub node:     LineNode,
+    /// Trailing whitespace (before the line ending)
+    pub trailing: String,
+    /// `"\n"`, `"\r\n"` or empty for the last line
+    pub ending:   String
+}
+
+/// Line content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineNode {
+    Blank,
+    /// `#` comment, including the `#`
+    Comment(String),
+    Directive(Directive),
+    Lyrics(Vec<Segment>)
+}
+
+/// `{name: value}` directive as written
+///
+/// Prints as `{` + `leading` + `name` + `separator` + `value` + `padding` +
+/// `}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    /// Whitespace after `{`
+    pub leading:   String,
+    /// Name as written (`soc`, `start_of_chorus`, `Title`)
+    pub name:      String,
+    /// Text between name and value, e.g. `": "`
+    pub separator: String,
+    /// Value, `None` when the directive has no separator
+    pub value:     Option<String>,
+    /// Whitespace before `}`
+    pub padding:   String
+}
+
+/// Piece of a lyrics line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    /// Content of `[...]`, without brackets
+    Chord(String)
+}
+
+impl SyntaxTree {
+    /// Parse source into a lossless tree
+    pub fn parse(content: &str) -> Self {
+        Self {
+            lines: content
+                .split_inclusive('\n')
+                .map(SyntaxLine::parse)
+                .collect()
+        }
+    }
+
+    /// Iterate over directives
+    pub fn directives(&self) -> impl Iterator<Item = &Directive> {
+        self.lines.iter().filter_map(SyntaxLine::directive)
+    }
+
+    /// Iterate mutably over directives
+    pub fn directives_mut(&mut self) -> impl Iterator<Item = &mut Directive> {
+        self.lines
+            .iter_mut()
+            .filter_map(|line| match &mut line.node {
+                LineNode::Directive(directive) => Some(directive),
+                _ => None
+            })
+    }
+
+    /// Iterate mutably over chord tokens (content of `[...]`)
+    pub fn chords_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        self.lines.iter_mut().flat_map(|line| match &mut line.node {
+            LineNode::Lyrics(segments) => segments
+                .iter_mut()
+                .filter_map(|segment| match segment {
+                    Segment::Chord(chord) => Some(chord),
+                    Segment::Text(_) => None
+                })
+                .collect(),
+            _ => Vec::new()
+        })
+    }
+
+    /// Set value of the first directive named `name` (case-insensitive)
+    ///
+    /// Only the value bytes change when the directive exists; otherwise a new
+    /// `{name: value}` line is inserted after the leading directives.
+    pub fn set_directive(&mut self, name: &str, value: &str) {
+        if let Some(directive) = self.directives_mut().find(|d| d.is(name)) {
+            if directive.value.is_none() {
+                directive.separator = ": ".to_string();
+            }
+            directive.value = Some(value.to_string());
+            return;
+        }
+
+        let index = self
+            .lines
+            .iter()
+            .position(|line| line.directive().is_none())
+            .unwrap_or(self.lines.len());
+        let ending = self
+            .lines
+            .iter()
+            .find(|line| !line.ending.is_empty())
+            .map_or("\n", |line| line.ending.as_str())
+            .to_string();
+
+        if index > 0 && self.lines[index - 1].ending.is_empty() {
+            self.lines[index - 1].ending = ending.clone();
+        }
+
+        self.lines.insert(
+            index,
+            SyntaxLine {
+                indent:   String::new(),
+                node:     LineNode::Directive(Directive::new(name, Some(value))),
+                trailing: String::new(),
+                ending:   if index == self.lines.len() {
+                    String::new()
+                } else {
+                    ending
+                }
+            }
+        );
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            write!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl SyntaxLine {
+    /// Parse a single line, including its line ending
+    pub fn parse(raw: &str) -> Self {
+        let (body, ending) = if let Some(body) = raw.strip_suffix("\r\n") {
+            (body, "\r\n")
+        } else if let Some(body) = raw.strip_suffix('\n') {
+            (body, "\n")
+        } else {
+            (raw, "")
+        };
+
+        let trimmed_start = body.trim_start();
+        let indent = &body[..body.len() - trimmed_start.len()];
+        let content = trimmed_start.trim_end();
+        let trailing = &trimmed_start[content.len()..];
+
+        let node = if content.is_empty() {
+            LineNode::Blank
+        } else if content.starts_with('#') {
+            LineNode::Comment(content.to_string())
+        } else if let Some(directive) = Directive::parse(content) {
+            LineNode::Directive(directive)
+        } else {
+            LineNode::Lyrics(Segment::parse_all(content))
+        };
+
+        Self {
+            indent: indent.to_string(),
+            node,
+            trailing: trailing.to_string(),
+            ending: ending.to_string()
+        }
+    }
+
+    /// Directive on this line, if any
+    pub fn directive(&self) -> Option<&Directive> {
+        match &self.node {
+            LineNode::Directive(directive) => Some(directive),
+            _ => None
+        }
+    }
+
+    /// Line content without indent, trailing whitespace and ending
+    pub fn content(&self) -> String {
+        self.node.to_string()
+    }
+}
+
+impl fmt::Display for SyntaxLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            self.indent, self.node, self.trailing, self.ending
+        )
+    }
+}
+
+impl fmt::Display for LineNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blank => Ok(()),
+            Self::Comment(text) => f.write_str(text),
+            Self::Directive(directive) => write!(f, "{}", directive),
+            Self::Lyrics(segments) => {
+                for segment in segments {
+                    write!(f, "{}", segment)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Directive {
+    /// Create directive with canonical `{name: value}` spacing
+    pub fn new(name: &str, value: Option<&str>) -> Self {
+        Self {
+            leading:   String::new(),
+            name:      name.to_string(),
+            separator: if value.is_some() {
+                ": ".to_string()
+            } else {
+                String::new()
+            },
+            value:     value.map(str::to_string),
+            padding:   String::new()
+        }
+    }
+
+    /// Parse directive from trimmed line content
+    pub fn parse(content: &str) -> Option<Self> {
+        let inner = content.strip_prefix('{')?.strip_suffix('}')?;
+        if inner.contains(['{', '}']) {
+            return None;
+        }
+
+        let rest = inner.trim_start();
+        let leading = &inner[..inner.len() - rest.len()];
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if name_len == 0 {
+            return None;
+        }
+        let name = &rest[..name_len];
+        let rest = &rest[name_len..];
+
+        let after_ws = rest.trim_start();
+        if after_ws.is_empty() {
+            return Some(Self {
+                leading:   leading.to_string(),
+                name:      name.to_string(),
+                separator: String::new(),
+                value:     None,
+                padding:   rest.to_string()
+            });
+        }
+
+        let after_colon = match after_ws.strip_prefix(':') {
+            Some(after) => after.trim_start(),
+            None if after_ws.len() < rest.len() => after_ws,
+            None => return None
+        };
+        let separator = &rest[..rest.len() - after_colon.len()];
+        let value = after_colon.trim_end();
+        let padding = &after_colon[value.len()..];
+
+        Some(Self {
+            leading:   leading.to_string(),
+            name:      name.to_string(),
+            separator: separator.to_string(),
+            value:     Some(value.to_string()),
+            padding:   padding.to_string()
+        })
+    }
+
+    /// Check directive name, ignoring case
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    /// Lowercase directive name
+    pub fn normalized_name(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    /// Character offset of the value from the opening `{`
+    pub fn value_offset(&self) -> usize {
+        1 + self.leading.chars().count()
+            + self.name.chars().count()
+            + self.separator.chars().count()
+    }
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{{}{}{}{}{}}}",
+            self.leading,
+            self.name,
+            self.separator,
+            self.value.as_deref().unwrap_or_default(),
+            self.padding
+        )
+    }
+}
+
+impl Segment {
+    /// Split lyrics content into text and `[chord]` segments
+    ///
+    /// An unclosed `[` and empty `[]` stay part of the text, as does `\[`:
+    /// a backslash escapes the character after it.
+    pub fn parse_all(content: &str) -> Vec<Self> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = content;
+
+        while let Some(open) = find_unescaped(rest, '[') {
+            let after = &rest[open + 1..];
+            match after.find(']') {
+                Some(close) if close > 0 => {
+                    text.push_str(&rest[..open]);
+                    if !text.is_empty() {
+                        segments.push(Self::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Self::Chord(after[..close].to_string()));
+                    rest = &after[close + 1..];
+                }
+                Some(close) => {
+                    text.push_str(&rest[..open + close + 2]);
+                    rest = &after[close + 1..];
+                }
+                None => break
+            }
+        }
+
+        text.push_str(rest);
+        if !text.is_empty() {
+            segments.push(Self::Text(text));
+        }
+
+        segments
+    }
+
+    /// Width of the segment in characters, as written
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Text(text) => text.chars().count(),
+            Self::Chord(chord) => chord.chars().count() + 2
+        }
+    }
+}
+
+/// Byte index of the first `target` not escaped by a backslash
+fn find_unescaped(text: &str, target: char) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == target {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+/// Byte index of the last `target` not escaped by a backslash
+pub(crate) fn rfind_unescaped(text: &str, target: char) -> Option<usize> {
+    let mut found = None;
+    let mut rest = text;
+    while let Some(idx) = find_unescaped(rest, target) {
+        found = Some(text.len() - rest.len() + idx);
+        rest = &rest[idx + target.len_utf8()..];
+    }
+    found
+}
+
+/// Lyrics text with backslash escapes resolved
+pub(crate) fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next().unwrap_or('\\')),
+            c => out.push(c)
+        }
+    }
+    out
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => f.write_str(text),
+            Self::Chord(chord) => write!(f, "[{}]", chord)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(content: &str) {
+        assert_eq!(SyntaxTree::parse(content).to_string(), content);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip("");
+        round_trip("\n");
+        round_trip("{title: Test}\n{key: G}\n\n[G]Amazing [C]grace\n");
+        round_trip("  {  soc  }  \r\n# comment\r\n\t[Am]x [ y\n[]\n{unknown:value}");
+        round_trip("{title Test}\n{ t :  spaced }\n{}\n{a}b}\n[G]");
+        round_trip("Привет [Am]мир\n   \n");
+    }
+
+    #[test]
+    fn test_parse_line_kinds() {
+        let tree = SyntaxTree::parse("# c\n\n{soc}\n[G]La");
+        assert_eq!(tree.lines.len(), 4);
+        assert_eq!(tree.lines[0].node, LineNode::Comment("# c".to_string()));
+        assert_eq!(tree.lines[1].node, LineNode::Blank);
+        assert_eq!(tree.lines[2].directive().unwrap().name, "soc");
+        assert_eq!(
+            tree.lines[3].node,
+            LineNode::Lyrics(vec![
+                Segment::Chord("G".to_string()),
+                Segment::Text("La".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_directive() {
+        let directive = Directive::parse("{ Title :  Amazing Grace }").unwrap();
+        assert_eq!(directive.leading, " ");
+        assert_eq!(directive.name, "Title");
+        assert_eq!(directive.separator, " :  ");
+        assert_eq!(directive.value.as_deref(), Some("Amazing Grace"));
+        assert_eq!(directive.padding, " ");
+        assert!(directive.is("title"));
+        assert_eq!(directive.value_offset(), 11);
+
+        let directive = Directive::parse("{key C}").unwrap();
+        assert_eq!(directive.value.as_deref(), Some("C"));
+
+        let directive = Directive::parse("{eoc }").unwrap();
+        assert!(directive.value.is_none());
+        assert_eq!(directive.padding, " ");
+    }
+
+    #[test]
+    fn test_parse_directive_invalid() {
+        assert!(Directive::parse("{}").is_none());
+        assert!(Directive::parse("{:x}").is_none());
+        assert!(Directive::parse("{a}b{c}").is_none());
+        assert!(Directive::parse("{a.b}").is_none());
+        assert!(Directive::parse("title").is_none());
+    }
+
+    #[test]
+    fn test_segments() {
+        let segments = Segment::parse_all("a [] b [C");
+        assert_eq!(segments, vec![Segment::Text("a [] b [C".to_string())]);
+
+        let segments = Segment::parse_all(r"\[C] \\[G]x");
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Text(r"\[C] \\".to_string()),
+                Segment::Chord("G".to_string()),
+                Segment::Text("x".to_string())
+            ]
+        );
+        assert_eq!(unescape(r"\[C] \\\{"), r"[C] \{");
+
+        let segments = Segment::parse_all("[C][G]x");
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[1].width(), 3);
+    }
+
+    #[test]
+    fn test_chords_mut_changes_only_chords() {
+        let mut tree = SyntaxTree::parse("{key: C}\n  [C]Hello  [G]world \n");
+        for chord in tree.chords_mut() {
+            chord.push('7');
+        }
+        assert_eq!(tree.to_string(), "{key: C}\n  [C7]Hello  [G7]world \n");
+    }
+
+    #[test]
+    fn test_set_directive_existing() {
+        let mut tree = SyntaxTree::parse("{Key:C}\n[C]x\n");
+        tree.set_directive("key", "D");
+        assert_eq!(tree.to_string(), "{Key:D}\n[C]x\n");
+
+        let mut tree = SyntaxTree::parse("{key}\n");
+        tree.set_directive("key", "D");
+        assert_eq!(tree.to_string(), "{key: D}\n");
+    }
+
+    #[test]
+    fn test_set_directive_inserts() {
+        let mut tree = SyntaxTree::parse("{title: T}\r\n[C]x\r\n");
+        tree.set_directive("key", "C");
+        assert_eq!(tree.to_string(), "{title: T}\r\n{key: C}\r\n[C]x\r\n");
+
+        let mut tree = SyntaxTree::parse("{title: T}");
+        tree.set_directive("key", "C");
+        assert_eq!(tree.to_string(), "{title: T}\n{key: C}");
+
+        let mut tree = SyntaxTree::default();
+        tree.set_directive("key", "C");
+        assert_eq!(tree.to_string(), "{key: C}");
+    }
+}
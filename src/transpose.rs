@@ -3,19 +3,84 @@
 
 //! Chord transposition logic
 //!
-//! Transposes chords in ChordPro content by a given number of semitones.
+//! Transposes chords by a given number of semitones, either on a parsed song
+//! or on ChordPro source through the lossless syntax tree.
+
+use super::{
+    Chord, ChordDefinition, ChordProParser, Directive, GridRow, Key, LineNode, Note, NoteNaming,
+    ParsedSong, Segment, SongSectionType, SyntaxTree, convert_naming
+};
+
+impl ParsedSong {
+    /// Transpose chords, `{key}`, chord grids and definitions by semitones
+    ///
+    /// Chords are spelled in the transposed key, taken from `{key}` or
+    /// detected from the chords. Definitions that cannot be moved (open
+    /// strings) are dropped.
+    pub fn transpose(&mut self, semitones: i32) {
+        if semitones == 0 {
+            return;
+        }
 
-use std::sync::LazyLock;
+        let target = self.estimated_key().map(|key| key.transpose(semitones));
+        self.transpose_into(semitones, target);
+        self.key = self
+            .key
+            .as_deref()
+            .map(|key| transpose_key(key, semitones, target.is_some_and(|key| key.uses_flats())));
+    }
 
-use regex::Regex;
+    /// Move chords and definitions, spelling chords in `target`
+    fn transpose_into(&mut self, semitones: i32, target: Option<Key>) {
+        let use_flats = target.is_some_and(|key| key.uses_flats());
 
-use super::Note;
+        self.map_chords(|chord| transpose_chord_in(chord, semitones, target.as_ref()));
+        self.definitions = self
+            .definitions
+            .iter()
+            .filter_map(|d| d.transpose(semitones, use_flats))
+            .collect();
+    }
+}
+
+/// Song transposed to `target` the shortest way (at most six semitones)
+///
+/// A target in the other mode is read as its relative key, so `C` for a song
+/// in `Am` means `Am`. Chords are spelled and `{key}` is set exactly as the
+/// target; `None` when the song key is unknown.
+pub fn transpose_to_key(song: &ParsedSong, target: &Key) -> Option<ParsedSong> {
+    let from = song.estimated_key()?;
+    let target = if target.mode == from.mode {
+        *target
+    } else {
+        target.relative()
+    };
 
-/// Regex for matching chords in brackets
-static CHORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]+)\]").unwrap());
+    let mut song = song.clone();
+    song.transpose_into(semitones_to_key(&from, &target), Some(target));
+    song.key = Some(target.to_string());
+    Some(song)
+}
 
-/// Regex for matching key directive
-static KEY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\{key:\s*([^}]+)\}").unwrap());
+/// Shortest signed distance between two keys, from -5 to 6 semitones
+///
+/// Keys in different modes are compared through the relative key of `to`.
+pub fn semitones_to_key(from: &Key, to: &Key) -> i32 {
+    let to = if to.mode == from.mode {
+        *to
+    } else {
+        to.relative()
+    };
+    shortest(to.tonic.note().to_semitone() as i32 - from.tonic.note().to_semitone() as i32)
+}
+
+/// Same transposition in the range -5..=6
+pub(crate) fn shortest(semitones: i32) -> i32 {
+    match semitones.rem_euclid(12) {
+        up if up > 6 => up - 12,
+        up => up
+    }
+}
 
 /// Transpose all chords in ChordPro content by given semitones
 ///
@@ -25,129 +90,136 @@ static KEY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\{key:\s*([^}
 ///   down)
 ///
 /// # Returns
-/// Content with all chords transposed
+/// Content with chords, chord grids, every `{key}` and chord definitions
+/// transposed; everything else is kept byte-identical. Chords are spelled in
+/// the transposed key in effect: the first `{key}` (or the key detected from
+/// chords) until the next `{key}`. Annotations (`[*riff]`) and verbatim
+/// sections other than grids are left alone. Definitions that cannot be
+/// moved (open strings) are dropped, as in [`ParsedSong::transpose`].
 pub fn transpose_content(content: &str, semitones: i32) -> String {
     if semitones == 0 {
         return content.to_string();
     }
 
-    let use_flats = should_use_flats(content, semitones);
+    let mut tree = SyntaxTree::parse(content);
+    let mut target = find_key(&tree).map(|key| key.transpose(semitones));
+    let mut verbatim: Option<SongSectionType> = None;
+
+    tree.lines.retain_mut(|line| {
+        let name = line.directive().map(Directive::normalized_name);
+        let use_flats = target.is_some_and(|key| key.uses_flats());
+
+        if let Some(kind) = verbatim {
+            if name.is_some_and(|name| ChordProParser::section_end(&name).is_some()) {
+                verbatim = None;
+            } else if kind == SongSectionType::Grid {
+                let text = line.node.to_string();
+                let mapped = GridRow::map_chords(&text, |chord| {
+                    transpose_chord_in(chord, semitones, target.as_ref())
+                });
+                if mapped != text {
+                    line.node = LineNode::Lyrics(vec![Segment::Text(mapped)]);
+                }
+            }
+            return true;
+        }
 
-    let content = KEY_RE.replace(content, |caps: &regex::Captures| {
-        let key = caps[1].trim();
-        let transposed = transpose_key(key, semitones, use_flats);
-        format!("{{key: {}}}", transposed)
+        match (&mut line.node, name.as_deref()) {
+            (LineNode::Directive(directive), Some(name)) => match (name, &mut directive.value) {
+                ("key", Some(value)) => {
+                    if let Some(key) = Key::parse(value) {
+                        target = Some(key.transpose(semitones));
+                    }
+                    *value = transpose_key(value, semitones, use_flats);
+                }
+                ("meta", Some(value)) => {
+                    if let Some(key) = value.strip_prefix("key ") {
+                        *value = format!("key {}", transpose_key(key, semitones, use_flats));
+                    }
+                }
+                ("define" | "chord", Some(value)) => {
+                    if let Some(definition) = ChordDefinition::parse(value) {
+                        match definition.transpose(semitones, use_flats) {
+                            Some(definition) => *value = definition.to_string(),
+                            None => return false
+                        }
+                    }
+                }
+                _ => {
+                    if let Some(kind) = ChordProParser::section_start(name) {
+                        let kind = ChordProParser::parse_section_type(&kind);
+                        verbatim = kind.is_verbatim().then_some(kind);
+                    }
+                }
+            },
+            (LineNode::Lyrics(segments), _) => {
+                for segment in segments {
+                    if let Segment::Chord(token) = segment
+                        && let Some(chord) = Chord::parse(token)
+                    {
+                        *token =
+                            transpose_chord_in(&chord, semitones, target.as_ref()).to_string();
+                    }
+                }
+            }
+            _ => {}
+        }
+        true
     });
 
-    CHORD_RE
-        .replace_all(&content, |caps: &regex::Captures| {
-            let chord = &caps[1];
-            let transposed = transpose_chord(chord, semitones, use_flats);
-            format!("[{}]", transposed)
-        })
-        .to_string()
+    tree.to_string()
 }
 
-/// Transpose a single key notation
-pub fn transpose_key(key: &str, semitones: i32, use_flats: bool) -> String {
-    transpose_chord(key, semitones, use_flats)
-}
-
-/// Transpose a single chord
-fn transpose_chord(chord: &str, semitones: i32, use_flats: bool) -> String {
-    let chord = chord.trim();
-    if chord.is_empty() {
-        return chord.to_string();
-    }
-
-    if let Some(slash_pos) = chord.rfind('/') {
-        let main = &chord[..slash_pos];
-        let bass = &chord[slash_pos + 1..];
-
-        let transposed_main = transpose_single_chord(main, semitones, use_flats);
-        let transposed_bass = transpose_note_in_string(bass, semitones, use_flats);
-
-        return format!("{}/{}", transposed_main, transposed_bass);
+/// Transpose ChordPro content written in the given note naming
+///
+/// Chords and `{key}` are read and written in `naming`; the rest works like
+/// [`transpose_content`].
+pub fn transpose_content_with(content: &str, semitones: i32, naming: NoteNaming) -> String {
+    if naming == NoteNaming::English {
+        return transpose_content(content, semitones);
     }
 
-    transpose_single_chord(chord, semitones, use_flats)
+    let english = convert_naming(content, naming, NoteNaming::English);
+    convert_naming(
+        &transpose_content(&english, semitones),
+        NoteNaming::English,
+        naming
+    )
 }
 
-/// Transpose a single chord (without slash)
-fn transpose_single_chord(chord: &str, semitones: i32, use_flats: bool) -> String {
-    let chars: Vec<char> = chord.chars().collect();
-    if chars.is_empty() {
-        return chord.to_string();
-    }
-
-    let (root_len, root_str) = if chars.len() >= 2 && (chars[1] == '#' || chars[1] == 'b') {
-        (2, format!("{}{}", chars[0], chars[1]))
-    } else {
-        (1, chars[0].to_string())
-    };
-
-    if let Some((note, _)) = Note::parse(&root_str) {
-        let transposed = note.transpose(semitones);
-        let new_root = if use_flats {
-            transposed.to_flat_string()
-        } else {
-            transposed.to_sharp_string()
-        };
-
-        let quality: String = chars[root_len..].iter().collect();
-        format!("{}{}", new_root, quality)
-    } else {
-        chord.to_string()
+/// Transpose a single key notation
+///
+/// Recognized keys get the spelling with the simpler key signature,
+/// anything else is transposed like a chord.
+pub fn transpose_key(key: &str, semitones: i32, use_flats: bool) -> String {
+    match Key::parse(key) {
+        Some(parsed) => parsed.transpose(semitones).to_string(),
+        None => transpose_chord(key, semitones, use_flats)
     }
 }
 
-/// Transpose just the note part of a string
-fn transpose_note_in_string(s: &str, semitones: i32, use_flats: bool) -> String {
-    let s = s.trim();
-    if s.is_empty() {
-        return s.to_string();
+/// Chord moved by semitones, spelled in `key` when it is known
+pub(crate) fn transpose_chord_in(chord: &Chord, semitones: i32, key: Option<&Key>) -> Chord {
+    match key {
+        Some(key) => chord.transpose_in_key(semitones, key),
+        None => chord.transpose(semitones, false)
     }
+}
 
-    let chars: Vec<char> = s.chars().collect();
-
-    let (root_len, root_str) = if chars.len() >= 2 && (chars[1] == '#' || chars[1] == 'b') {
-        (2, format!("{}{}", chars[0], chars[1]))
-    } else {
-        (1, chars[0].to_string())
-    };
-
-    if let Some((note, _)) = Note::parse(&root_str) {
-        let transposed = note.transpose(semitones);
-        let new_root = if use_flats {
-            transposed.to_flat_string()
-        } else {
-            transposed.to_sharp_string()
-        };
-
-        let rest: String = chars[root_len..].iter().collect();
-        format!("{}{}", new_root, rest)
-    } else {
-        s.to_string()
+/// Key from the first `{key}` directive, detected from chords without it
+fn find_key(tree: &SyntaxTree) -> Option<Key> {
+    match tree.directives().find(|d| d.is("key")) {
+        Some(directive) => directive.value.as_deref().and_then(Key::parse),
+        None => ChordProParser::parse_tree(tree).0.estimated_key()
     }
 }
 
-/// Determine if we should use flat notation based on the key
-fn should_use_flats(content: &str, semitones: i32) -> bool {
-    if let Some(caps) = KEY_RE.captures(content) {
-        let key = caps[1].trim();
-
-        if let Some((note, original_is_flat)) = Note::parse(key) {
-            let transposed = note.transpose(semitones);
-
-            if original_is_flat {
-                return true;
-            }
-
-            return matches!(transposed, Note::DSharp | Note::GSharp | Note::ASharp);
-        }
+/// Transpose a single chord, leaving text that is not a chord as it is
+fn transpose_chord(chord: &str, semitones: i32, use_flats: bool) -> String {
+    match Chord::parse(chord) {
+        Some(parsed) => parsed.transpose(semitones, use_flats).to_string(),
+        None => chord.trim().to_string()
     }
-
-    false
 }
 
 /// Get the number of semitones between two keys
@@ -190,6 +262,18 @@ mod tests {
         assert!(transposed.contains("[G]line"));
     }
 
+    #[test]
+    fn test_transpose_definitions() {
+        let content = "{define: F#m base-fret 2 frets 1 3 3 1 1 1}\n\
+                       {define: Asus2 base-fret 1 frets x 0 2 2 0 0}\n\
+                       {chord: Am}\n[F#m]Hello";
+        let transposed = transpose_content(content, 2);
+        assert_eq!(
+            transposed,
+            "{define: G#m base-fret 4 frets 1 3 3 1 1 1}\n{chord: Bm}\n[G#m]Hello"
+        );
+    }
+
     #[test]
     fn test_transpose_with_slash_chords() {
         let content = "[C/G]Hello [Am/E]world";
@@ -242,7 +326,7 @@ mod tests {
 
     #[test]
     fn test_transpose_empty_note() {
-        let result = transpose_note_in_string("", 2, false);
+        let result = transpose_key("", 2, false);
         assert_eq!(result, "");
     }
 
@@ -262,21 +346,109 @@ mod tests {
     }
 
     #[test]
-    fn test_should_use_flats_with_flat_key() {
+    fn test_transpose_flat_key() {
         let content = "{key: Bb}\n[Bb]Test";
-        assert!(should_use_flats(content, 1));
+        assert_eq!(transpose_content(content, 3), "{key: Db}\n[Db]Test");
+        assert_eq!(transpose_content(content, 1), "{key: B}\n[B]Test");
+    }
+
+    #[test]
+    fn test_transpose_spells_by_target_key() {
+        let content = "{key: C}\n[C]Hello [F]world [G/B]again [E7]";
+        let transposed = transpose_content(content, 5);
+        assert_eq!(transposed, "{key: F}\n[F]Hello [Bb]world [C/E]again [A7]");
+
+        let content = "{key: Fm}\n[Fm]Hello [Ab]world [Db/Ab]again [C7]";
+        let transposed = transpose_content(content, -2);
+        assert_eq!(
+            transposed,
+            "{key: Ebm}\n[Ebm]Hello [Gb]world [Cb/Gb]again [Bb7]"
+        );
+
+        let content = "{key: C}\n[C]x [Ab]y [Bb]z";
+        let transposed = transpose_content(content, 2);
+        assert_eq!(transposed, "{key: D}\n[D]x [Bb]y [C]z");
     }
 
     #[test]
-    fn test_should_use_flats_without_key() {
-        let content = "[C]Test";
-        assert!(!should_use_flats(content, 1));
+    fn test_transpose_content_with_naming() {
+        let content = "{key: G}\n[G]Hello [H7]world [Em]again";
+        let transposed = transpose_content_with(content, 3, NoteNaming::German);
+        assert_eq!(transposed, "{key: B}\n[B]Hello [D7]world [Gm]again");
+
+        let content = "{key: Ре}\n[Ре]Привет [Ля/До#]мир";
+        let transposed = transpose_content_with(content, 2, NoteNaming::SolfegeCyrillic);
+        assert_eq!(transposed, "{key: Ми}\n[Ми]Привет [Си/Ре#]мир");
     }
 
     #[test]
-    fn test_should_use_flats_transposed_to_flat_note() {
+    fn test_transpose_cyrillic_lookalikes() {
+        let content = "[С]Hello [Аm]world";
+        assert_eq!(transpose_content(content, 2), "[D]Hello [Bm]world");
+    }
+
+    #[test]
+    fn test_transpose_key_spelling() {
+        assert_eq!(transpose_key("C", 10, false), "Bb");
+        assert_eq!(transpose_key("G", 11, false), "F#");
+        assert_eq!(transpose_key("Fm", -2, false), "Ebm");
+        assert_eq!(transpose_key("A minor", 2, false), "Bm");
+    }
+
+    #[test]
+    fn test_transpose_spelling_without_key() {
+        assert_eq!(transpose_content("[C]Test", 1), "[Db]Test");
+        assert_eq!(transpose_content("[G]Test [D]Test", 2), "[A]Test [E]Test");
+    }
+
+    #[test]
+    fn test_transpose_without_key_uses_detected_key() {
+        let content = "[C]Hello [F]world [G]again [C]";
+        assert_eq!(
+            transpose_content(content, 3),
+            "[Eb]Hello [Ab]world [Bb]again [Eb]"
+        );
+    }
+
+    #[test]
+    fn test_transpose_to_flat_key() {
         let content = "{key: C}\n[C]Test";
-        assert!(should_use_flats(content, 3));
+        assert_eq!(transpose_content(content, 3), "{key: Eb}\n[Eb]Test");
+    }
+
+    #[test]
+    fn test_semitones_to_key() {
+        let key = |s: &str| Key::parse(s).unwrap();
+        assert_eq!(semitones_to_key(&key("C"), &key("D")), 2);
+        assert_eq!(semitones_to_key(&key("C"), &key("Bb")), -2);
+        assert_eq!(semitones_to_key(&key("G"), &key("C")), 5);
+        assert_eq!(semitones_to_key(&key("C"), &key("F#")), 6);
+        assert_eq!(semitones_to_key(&key("Am"), &key("C")), 0);
+        assert_eq!(semitones_to_key(&key("Am"), &key("Em")), -5);
+        assert_eq!(semitones_to_key(&key("G"), &key("Bm")), -5);
+    }
+
+    #[test]
+    fn test_transpose_to_key() {
+        let song = ChordProParser::parse("{key: G}\n[G]Hello [D/F#]world [Em]again");
+        let chords = |song: &ParsedSong| -> Vec<String> {
+            song.chords().iter().map(|c| c.to_string()).collect()
+        };
+
+        let moved = transpose_to_key(&song, &Key::parse("F#").unwrap()).unwrap();
+        assert_eq!(moved.key.as_deref(), Some("F#"));
+        assert_eq!(chords(&moved), ["F#", "C#/E#", "D#m"]);
+
+        let moved = transpose_to_key(&song, &Key::parse("Gb").unwrap()).unwrap();
+        assert_eq!(chords(&moved), ["Gb", "Db/F", "Ebm"]);
+
+        let moved = transpose_to_key(&song, &Key::parse("Cm").unwrap()).unwrap();
+        assert_eq!(moved.key.as_deref(), Some("Eb"));
+        assert_eq!(chords(&moved), ["Eb", "Bb/D", "Cm"]);
+
+        assert!(
+            transpose_to_key(&ChordProParser::parse("Hello"), &Key::parse("C").unwrap()).is_none()
+        );
     }
 
     #[test]
@@ -297,7 +469,7 @@ mod tests {
     fn test_transpose_sharp_chords() {
         let content = "[C#]Hello [F#]world";
         let transposed = transpose_content(content, 2);
-        assert_eq!(transposed, "[D#]Hello [G#]world");
+        assert_eq!(transposed, "[Eb]Hello [Ab]world");
     }
 
     #[test]
@@ -308,26 +480,82 @@ mod tests {
     }
 
     #[test]
-    fn test_transpose_single_chord_empty() {
-        let result = transpose_single_chord("", 2, false);
-        assert_eq!(result, "");
+    fn test_transpose_content_empty_brackets() {
+        let result = transpose_content("[] [ ]", 2);
+        assert_eq!(result, "[] [ ]");
     }
 
     #[test]
-    fn test_transpose_single_chord_invalid() {
-        let result = transpose_single_chord("123", 2, false);
+    fn test_transpose_chord_without_letter() {
+        let result = transpose_chord("123", 2, false);
         assert_eq!(result, "123");
     }
 
     #[test]
-    fn test_transpose_note_in_string_invalid() {
-        let result = transpose_note_in_string("XYZ", 2, false);
-        assert_eq!(result, "XYZ");
+    fn test_transpose_content_unknown_chord() {
+        let result = transpose_content("[XYZ]Hello", 2);
+        assert_eq!(result, "[XYZ]Hello");
     }
 
     #[test]
-    fn test_transpose_note_in_string_with_suffix() {
-        let result = transpose_note_in_string("C#m", 2, false);
+    fn test_transpose_chord_with_suffix() {
+        let result = transpose_chord("C#m", 2, false);
         assert_eq!(result, "D#m");
     }
+
+    #[test]
+    fn test_transpose_skips_annotations_and_tabs() {
+        let content = "[*Riff: A]Hello [C]world\n{start_of_tab}\ne|--[0]--3--|\n{end_of_tab}\n[F]";
+        assert_eq!(
+            transpose_content(content, 2),
+            "[*Riff: A]Hello [D]world\n{start_of_tab}\ne|--[0]--3--|\n{end_of_tab}\n[G]"
+        );
+    }
+
+    #[test]
+    fn test_transpose_every_key_directive() {
+        let content = "{key:C}\n[C]Hello [G]world\n{ key : A }\n[A]again [E/G#]more\n";
+        assert_eq!(
+            transpose_content(content, 1),
+            "{key:Db}\n[Db]Hello [Ab]world\n{ key : Bb }\n[Bb]again [F/A]more\n"
+        );
+
+        let content = "{meta: key G}\n[G]Hello";
+        assert_eq!(transpose_content(content, 2), "{meta: key A}\n[A]Hello");
+    }
+
+    #[test]
+    fn test_transpose_grid_keeps_layout() {
+        let content = "{key: G}\n{start_of_grid}\nA || G . . . | C  . D . | %  |\n{end_of_grid}\n";
+        assert_eq!(
+            transpose_content(content, 2),
+            "{key: A}\n{start_of_grid}\nA || A . . . | D  . E . | %  |\n{end_of_grid}\n"
+        );
+    }
+
+    #[test]
+    fn test_transpose_preserves_other_bytes() {
+        let content =
+            "{title: Song}\r\n  # [C] comment\r\n\t[C]Hello  [G]world  \r\n{c: [C] intro}";
+        assert_eq!(
+            transpose_content(content, 2),
+            "{title: Song}\r\n  # [C] comment\r\n\t[D]Hello  [A]world  \r\n{c: [C] intro}"
+        );
+    }
+
+    #[test]
+    fn test_transpose_parsed_song() {
+        let content = "{key: G}\n{define: G base-fret 3 frets 1 3 3 2 1 1}\n{define: Em frets 0 2 2 0 0 0}\n[G]Hello [D/F#]world\n{start_of_grid}\n| G . C . |\n{end_of_grid}";
+        let mut song = ChordProParser::parse(content);
+        song.transpose(3);
+
+        assert_eq!(song.key.as_deref(), Some("Bb"));
+        let chords: Vec<String> = song.chords().iter().map(|c| c.to_string()).collect();
+        assert_eq!(chords, ["Bb", "F/A", "Bb", "Eb"]);
+        assert_eq!(song.definitions.len(), 1);
+        assert_eq!(song.definitions[0].name, "Bb");
+        assert_eq!(song.definitions[0].base_fret, 6);
+
+        assert_eq!(song, ChordProParser::parse(&transpose_content(content, 3)));
+    }
 }
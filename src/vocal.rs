@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Vocal ranges and key suggestions for singers
+//!
+//! Pitches use scientific notation (`C4` is middle C). A melody range is
+//! moved through all twelve keys, an octave up or down when that fits
+//! better, and keys are ranked by how well the melody sits in the singer's
+//! range.
+
+use std::{cmp::Ordering, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Key, Letter, Note, NoteName, transpose::shortest};
+
+/// Note with octave, like `G3` or `Bb4`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Pitch {
+    pub name:   NoteName,
+    pub octave: i8
+}
+
+impl Pitch {
+    /// Parse pitch like `C4`, `F#3`, `Bb2`, `A-1`
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let split = s.find(|c: char| c.is_ascii_digit() || c == '-')?;
+        let (name, octave) = s.split_at(split);
+
+        Some(Self {
+            name:   NoteName::parse(name)?,
+            octave: octave.parse().ok()?
+        })
+    }
+
+    /// MIDI note number (`C4` = 60)
+    pub fn midi(self) -> i32 {
+        (self.octave as i32 + 1) * 12 + self.name.letter.semitone() as i32 + self.name.shift as i32
+    }
+
+    /// Pitch for a MIDI note number, spelled in `key` (sharps without one)
+    pub fn from_midi(midi: i32, key: Option<&Key>) -> Self {
+        let note = Note::from_semitone(midi.rem_euclid(12) as u8);
+        let name = match key {
+            Some(key) => key.spell(note),
+            None => {
+                let letter = Letter::ALL
+                    .into_iter()
+                    .rfind(|letter| letter.semitone() <= note.to_semitone())
+                    .unwrap_or(Letter::C);
+                NoteName::spelled_as(note, letter)
+            }
+        };
+        let natural = midi - name.letter.semitone() as i32 - name.shift as i32;
+
+        Self {
+            name,
+            octave: (natural.div_euclid(12) - 1) as i8
+        }
+    }
+
+    /// Pitch moved by semitones, spelled in `key`
+    pub fn transpose(self, semitones: i32, key: Option<&Key>) -> Self {
+        Self::from_midi(self.midi() + semitones, key)
+    }
+}
+
+impl PartialOrd for Pitch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pitch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.midi()
+            .cmp(&other.midi())
+            .then_with(|| self.name.letter.index().cmp(&other.name.letter.index()))
+    }
+}
+
+impl fmt::Display for Pitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.name, self.octave)
+    }
+}
+
+/// Lowest and highest note of a melody or a voice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct VocalRange {
+    pub low:  Pitch,
+    pub high: Pitch
+}
+
+impl VocalRange {
+    /// Range from two pitches, `None` when `low` is above `high`
+    pub fn new(low: Pitch, high: Pitch) -> Option<Self> {
+        (low <= high).then_some(Self {
+            low,
+            high
+        })
+    }
+
+    /// Parse range like `A3-E5` or `G2–C4`
+    pub fn parse(s: &str) -> Option<Self> {
+        let (low, high) = s
+            .split_once('–')
+            .or_else(|| s.split_once(" - "))
+            .or_else(|| {
+                // `-` also starts negative octaves, so split after the first
+                // octave number
+                let digit = s.find(|c: char| c.is_ascii_digit())?;
+                let dash = digit + s[digit..].find('-')?;
+                Some((&s[..dash], &s[dash + 1..]))
+            })?;
+        Self::new(Pitch::parse(low)?, Pitch::parse(high)?)
+    }
+
+    /// Size in semitones
+    pub fn span(&self) -> i32 {
+        self.high.midi() - self.low.midi()
+    }
+
+    /// Whether `other` lies completely inside this range
+    pub fn contains(&self, other: &VocalRange) -> bool {
+        self.low <= other.low && other.high <= self.high
+    }
+
+    /// Range moved by semitones, spelled in `key`
+    pub fn transpose(&self, semitones: i32, key: Option<&Key>) -> Self {
+        Self {
+            low:  self.low.transpose(semitones, key),
+            high: self.high.transpose(semitones, key)
+        }
+    }
+}
+
+impl fmt::Display for VocalRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.low, self.high)
+    }
+}
+
+/// Key to sing a song in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct KeySuggestion {
+    pub key:       Key,
+    /// Chord transposition from the song key, -5..=6
+    pub semitones: i32,
+    /// Melody range as sung, including an octave shift
+    pub melody:    VocalRange,
+    /// Semitones of the melody outside the singer's range
+    pub overflow:  i32
+}
+
+impl KeySuggestion {
+    /// Whether the whole melody is within the singer's range
+    pub fn fits(&self) -> bool {
+        self.overflow == 0
+    }
+}
+
+/// Rank all twelve keys for a singer, best first
+///
+/// Keys where the melody fits come first, ordered by how close the melody
+/// sits to the middle of the singer's range, then by the smallest
+/// transposition. Otherwise the fewest semitones out of range win.
+pub fn suggest_keys(
+    song_key: &Key,
+    melody: &VocalRange,
+    singer: &VocalRange
+) -> Vec<KeySuggestion> {
+    let mut suggestions: Vec<(KeySuggestion, i32)> = (-5..=6)
+        .map(|semitones| {
+            let key = song_key.transpose(semitones);
+            [semitones - 12, semitones, semitones + 12]
+                .into_iter()
+                .map(|shift| {
+                    let sung = melody.transpose(shift, Some(&key));
+                    let overflow = (singer.low.midi() - sung.low.midi()).max(0)
+                        + (sung.high.midi() - singer.high.midi()).max(0);
+                    let center = (sung.low.midi() + sung.high.midi()
+                        - singer.low.midi()
+                        - singer.high.midi())
+                    .abs();
+                    (
+                        KeySuggestion {
+                            key,
+                            semitones: shortest(semitones),
+                            melody: sung,
+                            overflow
+                        },
+                        center
+                    )
+                })
+                .min_by_key(|(suggestion, center)| (suggestion.overflow, *center))
+                .expect("three octave choices")
+        })
+        .collect();
+
+    suggestions.sort_by_key(|(suggestion, center)| {
+        (suggestion.overflow, *center, suggestion.semitones.abs())
+    });
+    suggestions
+        .into_iter()
+        .map(|(suggestion, _)| suggestion)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pitch(s: &str) -> Pitch {
+        Pitch::parse(s).unwrap()
+    }
+
+    fn range(s: &str) -> VocalRange {
+        VocalRange::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_pitch() {
+        assert_eq!(pitch("C4").midi(), 60);
+        assert_eq!(pitch("A4").midi(), 69);
+        assert_eq!(pitch("Bb2").midi(), 46);
+        assert_eq!(pitch("B#3").midi(), 60);
+        assert_eq!(pitch("Cb4").midi(), 59);
+        assert_eq!(pitch("A-1").midi(), 9);
+        assert_eq!(pitch("F#3").to_string(), "F#3");
+        assert!(Pitch::parse("C").is_none());
+        assert!(Pitch::parse("X4").is_none());
+    }
+
+    #[test]
+    fn test_from_midi() {
+        assert_eq!(Pitch::from_midi(60, None).to_string(), "C4");
+        assert_eq!(Pitch::from_midi(70, None).to_string(), "A#4");
+
+        let key = Key::parse("F").unwrap();
+        assert_eq!(Pitch::from_midi(70, Some(&key)).to_string(), "Bb4");
+
+        let key = Key::parse("C#").unwrap();
+        assert_eq!(Pitch::from_midi(60, Some(&key)).to_string(), "B#3");
+    }
+
+    #[test]
+    fn test_vocal_range() {
+        let alto = range("F3-D5");
+        assert_eq!(alto.span(), 21);
+        assert!(alto.contains(&range("A3–C5")));
+        assert!(!alto.contains(&range("E3 - C5")));
+        assert_eq!(range("C-1-G0").low.octave, -1);
+        assert!(VocalRange::parse("C5-C4").is_none());
+        assert_eq!(alto.transpose(2, None).to_string(), "G3-E5");
+    }
+
+    #[test]
+    fn test_suggest_keys() {
+        let key = Key::parse("G").unwrap();
+        let melody = range("D4-E5");
+        let singer = range("A3-C5");
+
+        let suggestions = suggest_keys(&key, &melody, &singer);
+        assert_eq!(suggestions.len(), 12);
+
+        let best = &suggestions[0];
+        assert!(best.fits());
+        assert!(singer.contains(&best.melody));
+        assert_eq!(best.key.to_string(), "Eb");
+        assert_eq!(best.semitones, -4);
+        assert_eq!(best.melody.to_string(), "Bb3-C5");
+
+        assert!(suggestions.iter().all(|s| s.melody.span() == melody.span()));
+        let overflows: Vec<i32> = suggestions.iter().map(|s| s.overflow).collect();
+        assert!(overflows.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_suggest_keys_octave_down() {
+        let key = Key::parse("C").unwrap();
+        let melody = range("E4-G5");
+        let singer = range("C3-A4");
+
+        let best = &suggest_keys(&key, &melody, &singer)[0];
+        assert!(best.fits());
+        assert_eq!(best.melody.low.octave, 3);
+    }
+}
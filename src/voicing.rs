@@ -0,0 +1,699 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! Chord voicings for fretted instruments
+//!
+//! Voicings come from three places, in order of precedence: `{define}`
+//! entries of the song, the built-in library of common shapes, and the
+//! generator, which searches four-fret windows along the neck and ranks
+//! fingerings by difficulty.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Chord, ChordDefinition, Note, ParsedSong};
+
+/// Highest fret the generator looks at
+const MAX_FRET: u8 = 12;
+
+/// Frets covered by the hand (window size)
+const HAND_SPAN: u8 = 4;
+
+/// Built-in guitar shapes, absolute frets from the lowest string
+const GUITAR_LIBRARY: &[(&str, &str)] = &[
+    ("C", "x32010"),
+    ("D", "xx0232"),
+    ("E", "022100"),
+    ("F", "133211"),
+    ("G", "320003"),
+    ("A", "x02220"),
+    ("B", "x24442"),
+    ("Cm", "x35543"),
+    ("Dm", "xx0231"),
+    ("Em", "022000"),
+    ("Fm", "133111"),
+    ("Gm", "355333"),
+    ("Am", "x02210"),
+    ("Bm", "x24432"),
+    ("C7", "x32310"),
+    ("D7", "xx0212"),
+    ("E7", "020100"),
+    ("G7", "320001"),
+    ("A7", "x02020"),
+    ("B7", "x21202"),
+    ("Dm7", "xx0211"),
+    ("Em7", "022030"),
+    ("Am7", "x02010"),
+    ("Cmaj7", "x32000"),
+    ("Fmaj7", "xx3210"),
+    ("Dsus4", "xx0233"),
+    ("Asus2", "x02200")
+];
+
+/// Built-in ukulele shapes (GCEA), absolute frets
+const UKULELE_LIBRARY: &[(&str, &str)] = &[
+    ("C", "0003"),
+    ("D", "2220"),
+    ("F", "2010"),
+    ("G", "0232"),
+    ("A", "2100"),
+    ("Dm", "2210"),
+    ("Em", "0432"),
+    ("Am", "2000"),
+    ("C7", "0001"),
+    ("E7", "1202"),
+    ("G7", "0212"),
+    ("A7", "0100")
+];
+
+/// Instrument with a standard tuning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Instrument {
+    #[default]
+    Guitar,
+    Ukulele,
+    Bass
+}
+
+impl Instrument {
+    /// Standard tuning
+    pub fn tuning(self) -> Tuning {
+        match self {
+            Self::Guitar => {
+                Tuning::new(vec![Note::E, Note::A, Note::D, Note::G, Note::B, Note::E])
+            }
+            Self::Ukulele => Tuning {
+                strings:      vec![Note::G, Note::C, Note::E, Note::A],
+                root_in_bass: false
+            },
+            Self::Bass => Tuning::new(vec![Note::E, Note::A, Note::D, Note::G])
+        }
+    }
+
+    fn library(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Guitar => GUITAR_LIBRARY,
+            Self::Ukulele => UKULELE_LIBRARY,
+            Self::Bass => &[]
+        }
+    }
+}
+
+/// Open-string notes, lowest string first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tuning {
+    pub strings:      Vec<Note>,
+    /// Whether the lowest string sounds lowest, so it must play the bass
+    /// note (false for re-entrant ukulele tuning)
+    pub root_in_bass: bool
+}
+
+impl Tuning {
+    pub fn new(strings: Vec<Note>) -> Self {
+        Self {
+            strings,
+            root_in_bass: true
+        }
+    }
+}
+
+/// Barre across several strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Barre {
+    /// Fret relative to `base_fret`
+    pub fret: u8,
+    /// Lowest string index (0 = lowest string)
+    pub from: usize,
+    /// Highest string index
+    pub to:   usize
+}
+
+/// Fingering of a chord
+///
+/// Frets use the same convention as [`ChordDefinition`]: relative to
+/// `base_fret`, `0` = open, `None` = muted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct Voicing {
+    pub base_fret:  u8,
+    pub frets:      Vec<Option<u8>>,
+    /// Finger per string (1 = index), `None` = not fretted
+    pub fingers:    Vec<Option<u8>>,
+    pub barre:      Option<Barre>,
+    /// Lower is easier
+    pub difficulty: u32
+}
+
+/// Diagram for the strip under a song
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+pub struct ChordDiagram {
+    /// Name to show (definition `display` or the chord as written)
+    pub name:    String,
+    pub voicing: Option<Voicing>
+}
+
+impl Voicing {
+    /// Build from absolute frets, assigning fingers when not given
+    ///
+    /// `None` when no string sounds or the shape needs more than four
+    /// fingers.
+    pub fn from_frets(frets: &[Option<u8>], fingers: Option<&[Option<u8>]>) -> Option<Self> {
+        if frets.iter().all(Option::is_none) {
+            return None;
+        }
+        let fretted: Vec<u8> = frets.iter().flatten().copied().filter(|f| *f > 0).collect();
+        let (low, high) = match (fretted.iter().min(), fretted.iter().max()) {
+            (Some(low), Some(high)) => (*low, *high),
+            _ => (1, 1)
+        };
+        let base_fret = if high <= HAND_SPAN { 1 } else { low };
+
+        let (fingers, barre) = match fingers {
+            Some(fingers)
+                if fingers.len() == frets.len() && fingers.iter().any(Option::is_some) =>
+            {
+                (fingers.to_vec(), find_barre(frets, fingers))
+            }
+            _ => assign_fingers(frets)?
+        };
+
+        let relative = frets
+            .iter()
+            .map(|fret| fret.map(|f| if f == 0 { 0 } else { f + 1 - base_fret }))
+            .collect();
+
+        Some(Self {
+            base_fret,
+            frets: relative,
+            difficulty: difficulty(frets, &fingers, barre.is_some()),
+            barre: barre.map(|b| Barre {
+                fret: b.fret + 1 - base_fret,
+                ..b
+            }),
+            fingers
+        })
+    }
+
+    /// Voicing of a `{define}` entry with frets
+    ///
+    /// `None` when a fret lies beyond the highest fret we can number.
+    pub fn from_definition(definition: &ChordDefinition) -> Option<Self> {
+        if !definition.has_frets() {
+            return None;
+        }
+        let absolute = definition
+            .frets
+            .iter()
+            .map(|fret| match fret {
+                Some(f) => absolute_fret(*f, definition.base_fret).map(Some),
+                None => Some(None)
+            })
+            .collect::<Option<Vec<Option<u8>>>>()?;
+        Self::from_frets(&absolute, Some(&definition.fingers))
+    }
+
+    /// Absolute fret per string; a fret out of range counts as muted
+    pub fn absolute_frets(&self) -> Vec<Option<u8>> {
+        self.frets
+            .iter()
+            .map(|fret| fret.and_then(|f| absolute_fret(f, self.base_fret)))
+            .collect()
+    }
+
+    /// Definition for writing back as `{define}`
+    pub fn to_definition(&self, name: &str) -> ChordDefinition {
+        ChordDefinition {
+            name:      name.to_string(),
+            base_fret: self.base_fret,
+            frets:     self.frets.clone(),
+            fingers:   self.fingers.clone(),
+            keys:      Vec::new(),
+            copy:      None,
+            display:   None
+        }
+    }
+}
+
+/// Generate playable voicings, easiest first
+///
+/// The lowest sounding string plays the bass note (or root) unless the
+/// tuning is re-entrant. A perfect fifth may be left out of chords with four
+/// or more tones.
+pub fn generate_voicings(chord: &Chord, tuning: &Tuning, limit: usize) -> Vec<Voicing> {
+    let Some(tones) = chord.tones() else {
+        return Vec::new();
+    };
+    let Some(bass) = chord.bass_note().or_else(|| chord.root_note()) else {
+        return Vec::new();
+    };
+    let bass = bass.note();
+
+    let allowed: HashSet<Note> = tones.iter().map(|t| t.note.note()).chain([bass]).collect();
+    let required: HashSet<Note> = tones
+        .iter()
+        .filter(|t| !(tones.len() >= 4 && t.interval.degree == 5 && t.interval.shift() == 0))
+        .map(|t| t.note.note())
+        .chain([bass])
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut voicings = Vec::new();
+    for start in 1..=MAX_FRET {
+        let options: Vec<Vec<Option<u8>>> = tuning
+            .strings
+            .iter()
+            .map(|open| {
+                let mut options = vec![None];
+                options.extend(
+                    std::iter::once(0)
+                        .chain(start..start + HAND_SPAN)
+                        .filter(|fret| allowed.contains(&open.transpose(*fret as i32)))
+                        .map(Some)
+                );
+                options
+            })
+            .collect();
+
+        let mut frets = Vec::with_capacity(options.len());
+        search(&options, &mut frets, &mut |frets| {
+            if seen.insert(frets.to_vec())
+                && is_complete(frets, tuning, &required, bass)
+                && let Some(voicing) = Voicing::from_frets(frets, None)
+            {
+                voicings.push(voicing);
+            }
+        });
+    }
+
+    voicings.sort_by(|a, b| {
+        a.difficulty
+            .cmp(&b.difficulty)
+            .then_with(|| a.absolute_frets().cmp(&b.absolute_frets()))
+    });
+    voicings.truncate(limit);
+    voicings
+}
+
+/// Voicing for a chord: song definition, built-in shape or generated
+pub fn chord_voicing(
+    chord: &Chord,
+    instrument: Instrument,
+    definitions: &[ChordDefinition]
+) -> Option<Voicing> {
+    let tuning = instrument.tuning();
+
+    if let Some(definition) = find_definition(chord, definitions)
+        && definition.frets.len() == tuning.strings.len()
+        && let Some(voicing) = Voicing::from_definition(definition)
+    {
+        return Some(voicing);
+    }
+
+    library_voicing(chord, instrument)
+        .or_else(|| generate_voicings(chord, &tuning, 1).into_iter().next())
+}
+
+/// Diagrams for every distinct chord of a song, in order of appearance
+pub fn chord_diagrams(song: &ParsedSong, instrument: Instrument) -> Vec<ChordDiagram> {
+    let mut names: Vec<String> = Vec::new();
+    let mut diagrams = Vec::new();
+
+    for chord in song.chords() {
+        let written = chord.to_string();
+        if names.contains(&written) {
+            continue;
+        }
+
+        let name = find_definition(chord, &song.definitions)
+            .and_then(|d| d.display.clone())
+            .unwrap_or_else(|| written.clone());
+        diagrams.push(ChordDiagram {
+            name,
+            voicing: chord_voicing(chord, instrument, &song.definitions)
+        });
+        names.push(written);
+    }
+
+    diagrams
+}
+
+/// Definition for the chord, following one `copy` reference
+fn find_definition<'a>(
+    chord: &Chord,
+    definitions: &'a [ChordDefinition]
+) -> Option<&'a ChordDefinition> {
+    let written = chord.to_string();
+    let definition = definitions.iter().rev().find(|d| d.name == written)?;
+
+    match &definition.copy {
+        Some(copy) if !definition.has_frets() => {
+            definitions.iter().rev().find(|d| &d.name == copy)
+        }
+        _ => Some(definition)
+    }
+}
+
+/// Fret counted from the nut for a fret relative to `base_fret`
+fn absolute_fret(fret: u8, base_fret: u8) -> Option<u8> {
+    if fret == 0 {
+        return Some(0);
+    }
+    (fret as u16 + base_fret as u16)
+        .checked_sub(1)
+        .and_then(|f| u8::try_from(f).ok())
+}
+
+fn library_voicing(chord: &Chord, instrument: Instrument) -> Option<Voicing> {
+    let (root, _) = Note::parse(&chord.root)?;
+    let structure = chord.structure.as_ref()?;
+    if chord.bass.is_some() {
+        return None;
+    }
+
+    instrument
+        .library()
+        .iter()
+        .find(|(name, _)| {
+            Chord::parse(name).is_some_and(|shape| {
+                Note::parse(&shape.root).is_some_and(|(note, _)| note == root)
+                    && shape.structure.as_ref() == Some(structure)
+            })
+        })
+        .and_then(|(_, frets)| {
+            let frets: Vec<Option<u8>> = frets
+                .chars()
+                .map(|c| c.to_digit(10).map(|d| d as u8))
+                .collect();
+            Voicing::from_frets(&frets, None)
+        })
+}
+
+/// Walk every combination of string options
+fn search(
+    options: &[Vec<Option<u8>>],
+    frets: &mut Vec<Option<u8>>,
+    visit: &mut impl FnMut(&[Option<u8>])
+) {
+    let Some(choices) = options.get(frets.len()) else {
+        visit(frets);
+        return;
+    };
+    for choice in choices {
+        frets.push(*choice);
+        search(options, frets, visit);
+        frets.pop();
+    }
+}
+
+fn is_complete(
+    frets: &[Option<u8>],
+    tuning: &Tuning,
+    required: &HashSet<Note>,
+    bass: Note
+) -> bool {
+    let sounding: Vec<Note> = frets
+        .iter()
+        .zip(&tuning.strings)
+        .filter_map(|(fret, open)| fret.map(|f| open.transpose(f as i32)))
+        .collect();
+
+    sounding.len() >= tuning.strings.len().min(3)
+        && required.iter().all(|note| sounding.contains(note))
+        && (!tuning.root_in_bass || sounding.first() == Some(&bass))
+}
+
+/// Fingers and barre for absolute frets; a barre is used only when more
+/// than four notes are fretted
+fn assign_fingers(frets: &[Option<u8>]) -> Option<(Vec<Option<u8>>, Option<Barre>)> {
+    let mut fretted: Vec<(usize, u8)> = frets
+        .iter()
+        .enumerate()
+        .filter_map(|(string, fret)| fret.filter(|f| *f > 0).map(|f| (string, f)))
+        .collect();
+    let mut fingers = vec![None; frets.len()];
+
+    let barre = if fretted.len() > 4 {
+        let low = fretted.iter().map(|(_, f)| *f).min()?;
+        let from = fretted.iter().find(|(_, f)| *f == low)?.0;
+        let to = fretted.iter().rev().find(|(_, f)| *f == low)?.0;
+        let covered = frets[from..=to].iter().all(|f| f.is_some_and(|f| f >= low));
+        (from < to && covered).then_some(Barre {
+            fret: low,
+            from,
+            to
+        })
+    } else {
+        None
+    };
+
+    let mut next = 1;
+    if let Some(barre) = barre {
+        fretted.retain(|(string, fret)| {
+            let under = *fret == barre.fret && (barre.from..=barre.to).contains(string);
+            if under {
+                fingers[*string] = Some(1);
+            }
+            !under
+        });
+        next = 2;
+    }
+
+    fretted.sort_by_key(|(string, fret)| (*fret, *string));
+    if fretted.len() + next as usize - 1 > 4 {
+        return None;
+    }
+    for (string, _) in fretted {
+        fingers[string] = Some(next);
+        next += 1;
+    }
+
+    Some((fingers, barre))
+}
+
+/// Barre from given fingers: one finger on the same fret over several strings
+fn find_barre(frets: &[Option<u8>], fingers: &[Option<u8>]) -> Option<Barre> {
+    let fretted: Vec<(usize, u8, u8)> = frets
+        .iter()
+        .zip(fingers)
+        .enumerate()
+        .filter_map(|(string, (fret, finger))| {
+            Some((string, fret.filter(|f| *f > 0)?, (*finger)?))
+        })
+        .collect();
+
+    fretted.iter().find_map(|(from, fret, finger)| {
+        let to = fretted
+            .iter()
+            .rev()
+            .find(|(_, f, g)| f == fret && g == finger)?
+            .0;
+        (to > *from).then_some(Barre {
+            fret: *fret,
+            from: *from,
+            to
+        })
+    })
+}
+
+fn difficulty(frets: &[Option<u8>], fingers: &[Option<u8>], barre: bool) -> u32 {
+    let fretted: Vec<u8> = frets.iter().flatten().copied().filter(|f| *f > 0).collect();
+    let span = match (fretted.iter().min(), fretted.iter().max()) {
+        (Some(low), Some(high)) => (high - low) as u32,
+        _ => 0
+    };
+    let position = fretted.iter().min().map_or(0, |low| (*low - 1) as u32);
+
+    let distinct_fingers = fingers.iter().flatten().collect::<HashSet<_>>().len() as u32;
+    let muted = frets.iter().filter(|f| f.is_none()).count() as u32;
+    let first = frets.iter().position(Option::is_some).unwrap_or(0);
+    let last = frets.iter().rposition(Option::is_some).unwrap_or(0);
+    let interior_muted = frets[first..=last].iter().filter(|f| f.is_none()).count() as u32;
+    let treble_muted = (frets.len() - 1 - last) as u32;
+
+    distinct_fingers * 2
+        + span * 3
+        + position
+        + if barre { 4 } else { 0 }
+        + muted * 2
+        + treble_muted * 4
+        + interior_muted * 6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChordProParser;
+
+    fn chord(s: &str) -> Chord {
+        Chord::parse(s).unwrap()
+    }
+
+    fn shape(voicing: &Voicing) -> String {
+        voicing
+            .absolute_frets()
+            .iter()
+            .map(|f| f.map_or("x".to_string(), |f| f.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_generate_open_chords() {
+        let guitar = Instrument::Guitar.tuning();
+        assert_eq!(
+            shape(&generate_voicings(&chord("C"), &guitar, 1)[0]),
+            "x32010"
+        );
+        assert_eq!(
+            shape(&generate_voicings(&chord("Em"), &guitar, 1)[0]),
+            "022000"
+        );
+        assert_eq!(
+            shape(&generate_voicings(&chord("D"), &guitar, 1)[0]),
+            "xx0232"
+        );
+    }
+
+    #[test]
+    fn test_generate_respects_bass() {
+        let guitar = Instrument::Guitar.tuning();
+        let voicings = generate_voicings(&chord("D/F#"), &guitar, 5);
+        assert!(!voicings.is_empty());
+        for voicing in &voicings {
+            let frets = voicing.absolute_frets();
+            let (string, fret) = frets
+                .iter()
+                .enumerate()
+                .find_map(|(i, f)| f.map(|f| (i, f)))
+                .unwrap();
+            assert_eq!(guitar.strings[string].transpose(fret as i32), Note::FSharp);
+        }
+    }
+
+    #[test]
+    fn test_generate_ranked_and_limited() {
+        let guitar = Instrument::Guitar.tuning();
+        let voicings = generate_voicings(&chord("G7"), &guitar, 4);
+        assert_eq!(voicings.len(), 4);
+        assert!(
+            voicings
+                .windows(2)
+                .all(|w| w[0].difficulty <= w[1].difficulty)
+        );
+    }
+
+    #[test]
+    fn test_generate_barre_chord() {
+        let guitar = Instrument::Guitar.tuning();
+        let voicing = generate_voicings(&chord("F#m"), &guitar, 10)
+            .into_iter()
+            .find(|v| shape(v) == "244222")
+            .unwrap();
+        assert_eq!(voicing.base_fret, 1);
+        assert_eq!(
+            voicing.barre,
+            Some(Barre {
+                fret: 2,
+                from: 0,
+                to:   5
+            })
+        );
+        assert_eq!(
+            voicing.fingers,
+            vec![Some(1), Some(2), Some(3), Some(1), Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn test_generate_ukulele_and_bass() {
+        let ukulele = Instrument::Ukulele.tuning();
+        assert_eq!(
+            shape(&generate_voicings(&chord("C"), &ukulele, 1)[0]),
+            "0003"
+        );
+
+        let bass = Instrument::Bass.tuning();
+        assert!(!generate_voicings(&chord("A"), &bass, 3).is_empty());
+    }
+
+    #[test]
+    fn test_generate_unknown_quality() {
+        let guitar = Instrument::Guitar.tuning();
+        assert!(generate_voicings(&chord("Cxyz"), &guitar, 3).is_empty());
+    }
+
+    #[test]
+    fn test_high_position_base_fret() {
+        let voicing =
+            Voicing::from_frets(&[None, Some(7), Some(9), Some(9), Some(8), Some(7)], None)
+                .unwrap();
+        assert_eq!(voicing.base_fret, 7);
+        assert_eq!(
+            voicing.frets,
+            vec![None, Some(1), Some(3), Some(3), Some(2), Some(1)]
+        );
+        assert_eq!(voicing.barre.unwrap().fret, 1);
+    }
+
+    #[test]
+    fn test_library_voicing() {
+        let voicing = chord_voicing(&chord("F"), Instrument::Guitar, &[]).unwrap();
+        assert_eq!(shape(&voicing), "133211");
+        assert_eq!(
+            voicing.fingers,
+            vec![Some(1), Some(3), Some(4), Some(2), Some(1), Some(1)]
+        );
+
+        let voicing = chord_voicing(&chord("Amin"), Instrument::Ukulele, &[]).unwrap();
+        assert_eq!(shape(&voicing), "2000");
+    }
+
+    #[test]
+    fn test_definitions_take_precedence() {
+        let definitions = [
+            ChordDefinition::parse("C base-fret 3 frets x 1 3 3 3 1 fingers - 1 2 3 4 1").unwrap(),
+            ChordDefinition::parse("Cadd9 frets x 3 2 0 3 0").unwrap()
+        ];
+
+        let voicing = chord_voicing(&chord("C"), Instrument::Guitar, &definitions).unwrap();
+        assert_eq!(voicing.base_fret, 3);
+        assert_eq!(shape(&voicing), "x35553");
+        assert_eq!(voicing.fingers[1], Some(1));
+
+        let voicing = chord_voicing(&chord("C"), Instrument::Ukulele, &definitions).unwrap();
+        assert_eq!(shape(&voicing), "0003");
+    }
+
+    #[test]
+    fn test_chord_diagrams() {
+        let song = ChordProParser::parse(
+            "{define: Bb base-fret 1 frets x 1 3 3 3 1 display B♭}\n[Bb]Hello [G]world [Bb]again [F#m7b5]"
+        );
+        let diagrams = chord_diagrams(&song, Instrument::Guitar);
+        let names: Vec<&str> = diagrams.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["B♭", "G", "F#m7b5"]);
+        assert_eq!(shape(diagrams[0].voicing.as_ref().unwrap()), "x13331");
+        assert_eq!(shape(diagrams[1].voicing.as_ref().unwrap()), "320003");
+        assert!(diagrams[2].voicing.is_some());
+    }
+
+    #[test]
+    fn test_definition_beyond_last_fret() {
+        let song = ChordProParser::parse("{define: C base-fret 255 frets x 3 3 3 3 3}\n[C]Hello");
+        assert_eq!(Voicing::from_definition(&song.definitions[0]), None);
+
+        let diagrams = chord_diagrams(&song, Instrument::Guitar);
+        assert_eq!(shape(diagrams[0].voicing.as_ref().unwrap()), "x32010");
+    }
+
+    #[test]
+    fn test_to_definition_round_trip() {
+        let voicing = chord_voicing(&chord("Bm"), Instrument::Guitar, &[]).unwrap();
+        let definition = voicing.to_definition("Bm");
+        assert_eq!(Voicing::from_definition(&definition).unwrap(), voicing);
+    }
+}
@@ -0,0 +1,529 @@
+// SPDX-FileCopyrightText: 2025 Revelation Team
+// SPDX-License-Identifier: MIT
+
+//! ChordPro writer
+//!
+//! Serializes structured songs back into ChordPro text.
+
+use super::{ParsedSong, SongLine, SongSection, SongSectionType};
+
+/// Directive spelling
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DirectiveStyle {
+    /// `{title}`, `{start_of_chorus}`
+    #[default]
+    Long,
+    /// `{t}`, `{soc}` where ChordPro defines an abbreviation
+    Short
+}
+
+/// ChordPro writer options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriterOptions {
+    pub directive_style: DirectiveStyle,
+    /// Emit section labels (`{start_of_verse: 1}`)
+    pub section_labels:  bool,
+    /// Emit title, key, tempo and other metadata directives
+    pub metadata:        bool
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            directive_style: DirectiveStyle::Long,
+            section_labels:  true,
+            metadata:        true
+        }
+    }
+}
+
+/// ChordPro format writer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChordProWriter {
+    options: WriterOptions
+}
+
+impl ChordProWriter {
+    pub fn new(options: WriterOptions) -> Self {
+        Self {
+            options
+        }
+    }
+
+    /// Serialize song into ChordPro content
+    ///
+    /// Lyrics are written as-is, so leading/trailing whitespace in line text
+    /// does not survive a parse round-trip.
+    pub fn write(&self, song: &ParsedSong) -> String {
+        let mut out = String::new();
+
+        if self.options.metadata {
+            self.write_metadata(song, &mut out);
+        }
+
+        for definition in &song.definitions {
+            out.push_str(&format!("{{define: {}}}\n", definition));
+        }
+
+        for section in &song.sections {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            self.write_section(section, &mut out);
+        }
+
+        out
+    }
+
+    fn write_metadata(&self, song: &ParsedSong, out: &mut String) {
+        let short = self.options.directive_style == DirectiveStyle::Short;
+        let fields = [
+            (if short { "t" } else { "title" }, song.title.clone()),
+            (if short { "st" } else { "subtitle" }, song.subtitle.clone()),
+            (if short { "a" } else { "artist" }, song.artist.clone()),
+            ("composer", song.composer.clone()),
+            ("key", song.key.clone()),
+            ("tempo", song.tempo.map(|t| t.to_string())),
+            ("time", song.time_signature.clone()),
+            ("capo", song.capo.map(|c| c.to_string()))
+        ];
+
+        for (name, value) in fields {
+            if let Some(value) = value {
+                out.push_str(&format!("{{{}: {}}}\n", name, value));
+            }
+        }
+    }
+
+    fn write_section(&self, section: &SongSection, out: &mut String) {
+        if section.recall.is_some() {
+            match section.label.as_deref() {
+                Some(label) if self.options.section_labels => {
+                    out.push_str(&format!("{{chorus: {}}}\n", label));
+                }
+                _ => out.push_str("{chorus}\n")
+            }
+            return;
+        }
+
+        let (start, end) = self.section_directives(section.section_type);
+
+        match section.label.as_deref() {
+            Some(label) if self.options.section_labels => {
+                out.push_str(&format!("{{{}: {}}}\n", start, label));
+            }
+            _ => out.push_str(&format!("{{{}}}\n", start))
+        }
+
+        for line in &section.lines {
+            if section.section_type.is_verbatim() {
+                out.push_str(&line.text);
+            } else {
+                out.push_str(&Self::write_line(line));
+            }
+            out.push('\n');
+        }
+
+        out.push_str(&format!("{{{}}}\n", end));
+    }
+
+    /// Directive names opening and closing a section
+    fn section_directives(&self, section_type: SongSectionType) -> (String, String) {
+        let kind = match section_type {
+            SongSectionType::Verse => "verse",
+            SongSectionType::Chorus => "chorus",
+            SongSectionType::Bridge => "bridge",
+            SongSectionType::PreChorus => "prechorus",
+            SongSectionType::Intro => "intro",
+            SongSectionType::Outro => "outro",
+            SongSectionType::Interlude => "interlude",
+            SongSectionType::Tag => "tag",
+            SongSectionType::Ending => "ending",
+            SongSectionType::Tab => "tab",
+            SongSectionType::Grid => "grid",
+            SongSectionType::Abc => "abc",
+            SongSectionType::Lilypond => "ly",
+            SongSectionType::Textblock => "textblock",
+            SongSectionType::Other => "other"
+        };
+
+        let short = match kind {
+            "verse" | "chorus" | "bridge" | "tab" | "grid" => &kind[..1],
+            _ => ""
+        };
+
+        if self.options.directive_style == DirectiveStyle::Short && !short.is_empty() {
+            (format!("so{}", short), format!("eo{}", short))
+        } else {
+            (format!("start_of_{}", kind), format!("end_of_{}", kind))
+        }
+    }
+
+    /// Serialize a line with inline chords
+    ///
+    /// Chords positioned past the end of the text are appended at the end.
+    /// Backslashes and `[` are escaped, as is a leading `{` or `#` that would
+    /// otherwise read as a directive or comment.
+    pub fn write_line(line: &SongLine) -> String {
+        let mut out = String::new();
+        let mut chords = line.chords.iter().peekable();
+        let start = line.text.chars().take_while(|c| c.is_whitespace()).count();
+
+        for (idx, ch) in line.text.chars().enumerate() {
+            while let Some(chord) = chords.next_if(|c| c.position <= idx) {
+                out.push_str(&format!("[{}]", chord.chord));
+            }
+            if matches!(ch, '\\' | '[') || (idx == start && matches!(ch, '{' | '#')) {
+                out.push('\\');
+            }
+            out.push(ch);
+        }
+
+        for chord in chords {
+            out.push_str(&format!("[{}]", chord.chord));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{option, prelude::*};
+
+    use super::*;
+    use crate::{Chord, ChordDefinition, ChordProParser, PositionedChord};
+
+    fn song_with(sections: Vec<SongSection>) -> ParsedSong {
+        ParsedSong {
+            title: Some("Amazing Grace".to_string()),
+            subtitle: None,
+            artist: None,
+            composer: None,
+            key: Some("G".to_string()),
+            tempo: Some(72),
+            time_signature: Some("3/4".to_string()),
+            capo: None,
+            definitions: Vec::new(),
+            sections
+        }
+    }
+
+    fn verse() -> SongSection {
+        SongSection {
+            section_type: SongSectionType::Verse,
+            label:        Some("1".to_string()),
+            lines:        vec![SongLine {
+                text:   "Amazing grace".to_string(),
+                chords: vec![
+                    PositionedChord {
+                        position: 0,
+                        chord:    Chord::parse("G").unwrap()
+                    },
+                    PositionedChord {
+                        position: 8,
+                        chord:    Chord::parse("G7").unwrap()
+                    },
+                ]
+            }],
+            recall:       None,
+            grid:         None
+        }
+    }
+
+    #[test]
+    fn test_write_song() {
+        let written = ChordProWriter::default().write(&song_with(vec![verse()]));
+        assert_eq!(
+            written,
+            "{title: Amazing Grace}\n{key: G}\n{tempo: 72}\n{time: 3/4}\n\n\
+             {start_of_verse: 1}\n[G]Amazing [G7]grace\n{end_of_verse}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_short_style_without_labels_and_metadata() {
+        let writer = ChordProWriter::new(WriterOptions {
+            directive_style: DirectiveStyle::Short,
+            section_labels:  false,
+            metadata:        false
+        });
+        let written = writer.write(&song_with(vec![verse()]));
+        assert_eq!(written, "{sov}\n[G]Amazing [G7]grace\n{eov}\n");
+    }
+
+    #[test]
+    fn test_write_short_title() {
+        let writer = ChordProWriter::new(WriterOptions {
+            directive_style: DirectiveStyle::Short,
+            ..WriterOptions::default()
+        });
+        let written = writer.write(&song_with(Vec::new()));
+        assert!(written.starts_with("{t: Amazing Grace}\n"));
+    }
+
+    #[test]
+    fn test_write_section_without_short_form() {
+        let writer = ChordProWriter::new(WriterOptions {
+            directive_style: DirectiveStyle::Short,
+            ..WriterOptions::default()
+        });
+        let mut section = verse();
+        section.section_type = SongSectionType::Intro;
+        section.label = None;
+        let written = writer.write(&song_with(vec![section]));
+        assert!(written.contains("{start_of_intro}\n"));
+        assert!(written.contains("{end_of_intro}\n"));
+    }
+
+    #[test]
+    fn test_write_definitions() {
+        let mut song = song_with(Vec::new());
+        song.definitions
+            .push(ChordDefinition::parse("Asus2 frets x 0 2 2 0 0").unwrap());
+        let written = ChordProWriter::default().write(&song);
+        assert!(written.ends_with("{define: Asus2 base-fret 1 frets x 0 2 2 0 0}\n"));
+    }
+
+    #[test]
+    fn test_write_chorus_recall() {
+        let mut chorus = verse();
+        chorus.section_type = SongSectionType::Chorus;
+        let recall = SongSection {
+            section_type: SongSectionType::Chorus,
+            label:        Some("Again".to_string()),
+            lines:        Vec::new(),
+            recall:       Some(0),
+            grid:         None
+        };
+        let song = song_with(vec![chorus, recall]);
+
+        let written = ChordProWriter::default().write(&song);
+        assert!(written.ends_with("{end_of_chorus}\n\n{chorus: Again}\n"));
+
+        let writer = ChordProWriter::new(WriterOptions {
+            section_labels: false,
+            ..WriterOptions::default()
+        });
+        assert!(writer.write(&song).ends_with("{chorus}\n"));
+    }
+
+    #[test]
+    fn test_write_verbatim_sections() {
+        let content = "{start_of_tab: Riff}\ne|--[0]--|\n  B|--1--|\n{end_of_tab}\n\n\
+                       {start_of_grid}\n| G . . . | C . . . |\n{end_of_grid}\n";
+        let song = ChordProParser::parse(content);
+        assert_eq!(ChordProWriter::default().write(&song), content);
+
+        let writer = ChordProWriter::new(WriterOptions {
+            directive_style: DirectiveStyle::Short,
+            ..WriterOptions::default()
+        });
+        let written = writer.write(&song);
+        assert!(written.starts_with("{sot: Riff}\n"));
+        assert!(written.contains("{sog}\n"));
+        assert_eq!(ChordProParser::parse(&written), song);
+    }
+
+    #[test]
+    fn test_write_line_chords_past_end() {
+        let line = SongLine {
+            text:   "Hi".to_string(),
+            chords: vec![
+                PositionedChord {
+                    position: 1,
+                    chord:    Chord::parse("C").unwrap()
+                },
+                PositionedChord {
+                    position: 1,
+                    chord:    Chord::parse("G/B").unwrap()
+                },
+                PositionedChord {
+                    position: 5,
+                    chord:    Chord::parse("Am").unwrap()
+                },
+            ]
+        };
+        assert_eq!(ChordProWriter::write_line(&line), "H[C][G/B]i[Am]");
+    }
+
+    #[test]
+    fn test_write_cyrillic_positions() {
+        let song = ChordProParser::parse("[Am]Привет [G]мир");
+        let written = ChordProWriter::default().write(&song);
+        assert!(written.contains("[Am]Привет [G]мир\n"));
+    }
+
+    #[test]
+    fn test_write_line_escapes_lyrics() {
+        for text in [
+            "{title: Not a directive}",
+            "# not a comment",
+            "a [C] in text",
+            "back\\slash"
+        ] {
+            let mut song = ChordProParser::parse("x");
+            song.sections[0].lines[0] = SongLine {
+                text:   text.to_string(),
+                chords: vec![PositionedChord {
+                    position: 2,
+                    chord:    Chord::parse("G").unwrap()
+                }]
+            };
+            let written = ChordProWriter::default().write(&song);
+            assert_eq!(ChordProParser::parse(&written), song, "{}", written);
+        }
+
+        let line = SongLine {
+            text:   "{x} #[y]\\".to_string(),
+            chords: Vec::new()
+        };
+        assert_eq!(ChordProWriter::write_line(&line), "\\{x} #\\[y]\\\\");
+    }
+
+    fn chord_strategy() -> impl Strategy<Value = Chord> {
+        let roots = prop::sample::select(vec!["C", "C#", "Db", "D", "Eb", "E", "F", "F#", "G"]);
+        let qualities = prop::sample::select(vec!["", "m", "7", "m7", "maj7", "sus4", "dim"]);
+        let basses = option::of(prop::sample::select(vec!["A", "B", "Bb", "E", "G#"]));
+        (roots, qualities, basses).prop_map(|(root, quality, bass)| {
+            let symbol = match bass {
+                Some(bass) => format!("{}{}/{}", root, quality, bass),
+                None => format!("{}{}", root, quality)
+            };
+            Chord::parse(&symbol).unwrap()
+        })
+    }
+
+    fn line_strategy() -> impl Strategy<Value = SongLine> {
+        (
+            r"([a-zA-Zа-я\[\]{}#\\]{1,6}( [a-zA-Zа-я\[\]{}#\\]{1,6}){0,3})?",
+            prop::collection::vec((0usize..30, chord_strategy()), 0..4)
+        )
+            .prop_map(|(text, chords)| {
+                let len = text.chars().count();
+                let mut chords: Vec<_> = chords
+                    .into_iter()
+                    .map(|(position, chord)| PositionedChord {
+                        position: position.min(len),
+                        chord
+                    })
+                    .collect();
+                chords.sort_by_key(|c| c.position);
+                SongLine {
+                    text,
+                    chords
+                }
+            })
+    }
+
+    fn section_strategy() -> impl Strategy<Value = SongSection> {
+        let types = prop::sample::select(vec![
+            SongSectionType::Verse,
+            SongSectionType::Chorus,
+            SongSectionType::Bridge,
+            SongSectionType::PreChorus,
+            SongSectionType::Intro,
+            SongSectionType::Outro,
+            SongSectionType::Interlude,
+            SongSectionType::Tag,
+            SongSectionType::Ending,
+            SongSectionType::Other,
+        ]);
+        (
+            types,
+            option::of("[A-Za-z0-9]{1,5}"),
+            prop::collection::vec(line_strategy(), 1..5)
+        )
+            .prop_map(|(section_type, label, lines)| SongSection {
+                section_type,
+                label,
+                lines,
+                recall: None,
+                grid: None
+            })
+    }
+
+    fn definition_strategy() -> impl Strategy<Value = ChordDefinition> {
+        prop::sample::select(vec![
+            "Asus2 base-fret 1 frets x 0 2 2 0 0 fingers - - 1 2 - -",
+            "F#m base-fret 2 frets 1 3 3 1 1 1",
+            "Cmaj7 keys 0 4 7 11",
+            "Bb7 copy A7",
+        ])
+        .prop_map(|value| ChordDefinition::parse(value).unwrap())
+    }
+
+    fn song_strategy() -> impl Strategy<Value = ParsedSong> {
+        let text = "[A-Za-z]([A-Za-z ]{0,10}[A-Za-z])?";
+        (
+            (
+                option::of(text),
+                option::of(text),
+                option::of(text),
+                option::of(text)
+            ),
+            (
+                option::of(prop::sample::select(vec!["C", "Am", "F#m", "Bb"])),
+                option::of(1i32..300),
+                option::of(prop::sample::select(vec!["3/4", "4/4", "6/8"])),
+                option::of(0i32..12)
+            ),
+            prop::collection::vec(definition_strategy(), 0..3),
+            prop::collection::vec(section_strategy(), 0..5),
+            (any::<bool>(), option::of("[A-Za-z0-9]{1,5}"))
+        )
+            .prop_map(
+                |(
+                    (title, subtitle, artist, composer),
+                    (key, tempo, time, capo),
+                    definitions,
+                    mut sections,
+                    (recall, recall_label)
+                )| {
+                    let chorus = sections
+                        .iter()
+                        .rposition(|s| s.section_type == SongSectionType::Chorus);
+                    if recall && let Some(index) = chorus {
+                        sections.push(SongSection {
+                            section_type: SongSectionType::Chorus,
+                            label:        recall_label,
+                            lines:        Vec::new(),
+                            recall:       Some(index),
+                            grid:         None
+                        });
+                    }
+                    ParsedSong {
+                        title,
+                        subtitle,
+                        artist,
+                        composer,
+                        key: key.map(str::to_string),
+                        tempo,
+                        time_signature: time.map(str::to_string),
+                        capo,
+                        definitions,
+                        sections
+                    }
+                }
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_write_round_trip(song in song_strategy()) {
+            let written = ChordProWriter::default().write(&song);
+            let (parsed, diagnostics) = ChordProParser::parse_with_diagnostics(&written);
+            prop_assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+            prop_assert_eq!(parsed, song);
+        }
+
+        #[test]
+        fn prop_short_style_round_trip(song in song_strategy()) {
+            let writer = ChordProWriter::new(WriterOptions {
+                directive_style: DirectiveStyle::Short,
+                ..WriterOptions::default()
+            });
+            prop_assert_eq!(ChordProParser::parse(&writer.write(&song)), song);
+        }
+    }
+}